@@ -0,0 +1,81 @@
+//! Measures the cost of one monitor poll.
+//!
+//! "before" replays what a single tick used to do per candidate pid: `ps` for
+//! argv, `ps` twice for `pcpu` and `lsof` for the cwd. "after" runs the same
+//! queries through the in-process `ProcessInspector`, plus the process
+//! snapshot every poll takes. "summary" is the whole poll the app, CLI and
+//! MCP server run each tick: every installed monitor, session files included.
+//!
+//! Run with `cargo bench --bench poll`.
+
+#[allow(dead_code)]
+#[path = "../src/agent_manager.rs"]
+mod agent_manager;
+#[allow(dead_code)]
+#[path = "../src/agents/mod.rs"]
+mod agents;
+#[allow(dead_code)]
+#[path = "../src/hooks.rs"]
+mod hooks;
+#[allow(dead_code)]
+#[path = "../src/paths.rs"]
+mod paths;
+#[allow(dead_code)]
+#[path = "../src/process/mod.rs"]
+mod process;
+#[allow(dead_code)]
+#[path = "../src/projects.rs"]
+mod projects;
+
+use std::process::Command;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 50;
+
+fn spawn_poll(pid: i32) {
+    let pid = pid.to_string();
+    let _ = Command::new("ps").args(["-p", &pid, "-o", "args="]).output();
+    let _ = Command::new("ps").args(["-p", &pid, "-o", "pcpu="]).output();
+    let _ = Command::new("ps").args(["-p", &pid, "-o", "pcpu="]).output();
+    let _ = Command::new("lsof").args(["-a", "-d", "cwd", "-p", &pid, "-F", "n"]).output();
+}
+
+fn inspector_poll(inspector: &Arc<dyn process::ProcessInspector>, pid: i32) {
+    use process::ProcessInspector;
+
+    let processes = process::ProcessSnapshot::take(inspector.clone());
+    let _ = processes.argv(pid);
+    let _ = processes.cwd(pid);
+    let _ = processes.cpu_times(pid);
+}
+
+fn measure(name: &str, mut poll: impl FnMut()) -> Duration {
+    // Warm up caches before timing
+    poll();
+
+    let started = Instant::now();
+    for _ in 0..ITERATIONS {
+        poll();
+    }
+    let per_poll = started.elapsed() / ITERATIONS;
+    println!("{:<8} {:>10.3} ms/poll", name, per_poll.as_secs_f64() * 1000.0);
+    per_poll
+}
+
+fn main() {
+    let pid = std::process::id() as i32;
+    let inspector = process::default_inspector();
+
+    let before = measure("before", || spawn_poll(pid));
+    let after = measure("after", || inspector_poll(&inspector, pid));
+
+    if after > Duration::ZERO {
+        println!("speedup  {:>10.1}x", before.as_secs_f64() / after.as_secs_f64());
+    }
+
+    let manager = agent_manager::AgentManager::new();
+    measure("summary", || {
+        manager.get_summary();
+    });
+}
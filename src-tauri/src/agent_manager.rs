@@ -1,42 +1,140 @@
-use crate::agents::{AgentMonitor, AgentInfo, AgentStatus, claude_code::ClaudeCodeMonitor, gemini::GeminiMonitor};
+use crate::agents::{aider::AiderMonitor, aggregate_status, is_same_project, AgentMonitor, AgentInfo, AgentInstance, AgentStatus, claude_code::ClaudeCodeMonitor, codex::CodexMonitor, configured::{AgentDefinition, ConfiguredMonitor}, copilot::CopilotCliMonitor, cursor_agent::CursorAgentMonitor, gemini::GeminiMonitor};
+use crate::hooks::HookState;
+use crate::process::{self, ProcessInspector, ProcessSnapshot};
+use crate::projects::Projects;
+use std::sync::Arc;
+
+/// A monitor and whether its agent is installed. That is checked once, since
+/// the check may spawn the agent's CLI (`claude --version` etc.).
+struct Monitored {
+    monitor: Box<dyn AgentMonitor>,
+    available: bool,
+}
+
+impl Monitored {
+    fn new(monitor: impl AgentMonitor + 'static) -> Self {
+        Self {
+            available: monitor.is_available(),
+            monitor: Box::new(monitor),
+        }
+    }
+}
 
 pub struct AgentManager {
-    monitors: Vec<Box<dyn AgentMonitor>>,
+    monitors: Vec<Monitored>,
+    inspector: Arc<dyn ProcessInspector>,
+    projects: Arc<Projects>,
+    hooks: Arc<HookState>,
 }
 
 impl AgentManager {
     pub fn new() -> Self {
-        let mut monitors: Vec<Box<dyn AgentMonitor>> = vec![];
+        let mut monitors: Vec<Monitored> = vec![];
+        let inspector = process::default_inspector();
+        let projects = Arc::new(Projects::new());
+        let hooks = Arc::new(HookState::new());
         
         // Add Claude Code monitor
-        let claude_monitor = ClaudeCodeMonitor::new();
-        if claude_monitor.is_available() {
-            monitors.push(Box::new(claude_monitor));
+        let claude_monitor = Monitored::new(ClaudeCodeMonitor::new(inspector.clone(), projects.clone(), hooks.clone()));
+        if claude_monitor.available {
+            monitors.push(claude_monitor);
+        }
+        
+        // Add Codex monitor
+        let codex_monitor = Monitored::new(CodexMonitor::new(inspector.clone(), projects.clone()));
+        if codex_monitor.available {
+            monitors.push(codex_monitor);
+        }
+        
+        // Add Aider monitor
+        let aider_monitor = Monitored::new(AiderMonitor::new(inspector.clone(), projects.clone()));
+        if aider_monitor.available {
+            monitors.push(aider_monitor);
+        }
+        
+        // Add Cursor Agent monitor
+        let cursor_monitor = Monitored::new(CursorAgentMonitor::new(inspector.clone(), projects.clone()));
+        if cursor_monitor.available {
+            monitors.push(cursor_monitor);
+        }
+        
+        // Add Copilot CLI monitor
+        let copilot_monitor = Monitored::new(CopilotCliMonitor::new(inspector.clone(), projects.clone()));
+        if copilot_monitor.available {
+            monitors.push(copilot_monitor);
         }
         
         // Add Gemini monitor
-        let gemini_monitor = GeminiMonitor::new();
-        if gemini_monitor.is_available() {
-            monitors.push(Box::new(gemini_monitor));
+        let gemini_monitor = Monitored::new(GeminiMonitor::new(inspector.clone(), projects.clone()));
+        if gemini_monitor.available {
+            monitors.push(gemini_monitor);
+        }
+        
+        // Add agents defined in agents.toml
+        for definition in AgentDefinition::load() {
+            match ConfiguredMonitor::new(&definition, inspector.clone(), projects.clone()) {
+                Ok(monitor) => monitors.push(Monitored::new(monitor)),
+                Err(e) => eprintln!("checka: ignoring agent {}", e),
+            }
         }
         
         Self {
             monitors,
+            inspector,
+            projects,
+            hooks,
         }
     }
 
+    /// Hook events reported by Claude Code sessions, fed by `hooks::listen`.
+    pub fn hook_state(&self) -> Arc<HookState> {
+        self.hooks.clone()
+    }
+
+    /// The project directories scanned for agents.
+    pub fn projects(&self) -> Arc<Projects> {
+        self.projects.clone()
+    }
+
 
+    /// One process snapshot serves every monitor for this poll.
     pub fn get_all_agent_info(&self) -> Vec<AgentInfo> {
+        let processes = ProcessSnapshot::take(self.inspector.clone());
         self.monitors
             .iter()
-            .map(|monitor| monitor.get_info())
+            .map(|monitored| monitored.monitor.get_info(&processes, monitored.available))
             .collect()
     }
 
     pub fn get_summary(&self) -> AgentSummary {
         // 한 번만 모든 에이전트 정보를 가져와서 재사용
         let agent_info = self.get_all_agent_info();
-        
+
+        let current_directory = std::env::current_dir()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|_| "Unknown".to_string());
+
+        AgentSummary::new(agent_info, self.monitors.len(), current_directory, self.projects.list())
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AgentSummary {
+    pub total_agents: usize,
+    pub processing_count: usize,
+    pub waiting_count: usize,
+    pub needs_input_count: usize,
+    pub active_count: usize,
+    pub agents: Vec<AgentInfo>,
+    pub last_updated: String,
+    pub current_directory: String,
+    /// Every monitored project directory, `current_directory` first
+    #[serde(default)]
+    pub projects: Vec<String>,
+}
+
+impl AgentSummary {
+    pub fn new(agent_info: Vec<AgentInfo>, total_agents: usize, current_directory: String, projects: Vec<String>) -> Self {
         // agent_info에서 카운트 정보를 계산 (get_status() 재호출 없음)
         let processing_count = agent_info.iter()
             .filter(|info| matches!(info.status, AgentStatus::Processing))
@@ -44,34 +142,48 @@ impl AgentManager {
         let waiting_count = agent_info.iter()
             .filter(|info| matches!(info.status, AgentStatus::Waiting))
             .count();
-        
-        let total_agents = self.monitors.len();
-        
-        let current_directory = std::env::current_dir()
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_else(|_| "Unknown".to_string());
+        let needs_input_count = agent_info.iter()
+            .filter(|info| matches!(info.status, AgentStatus::NeedsInput { .. }))
+            .count();
 
         AgentSummary {
             total_agents,
             processing_count,
             waiting_count,
-            active_count: processing_count + waiting_count,
+            needs_input_count,
+            active_count: processing_count + waiting_count + needs_input_count,
             agents: agent_info,
             last_updated: chrono::Local::now().format("%H:%M:%S").to_string(),
             current_directory,
+            projects,
         }
     }
-}
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct AgentSummary {
-    pub total_agents: usize,
-    pub processing_count: usize,
-    pub waiting_count: usize,
-    pub active_count: usize,
-    pub agents: Vec<AgentInfo>,
-    pub last_updated: String,
-    pub current_directory: String,
+    /// The summary narrowed to instances running in `dir`, with agent
+    /// statuses and counts recomputed.
+    pub fn for_project(&self, dir: &str) -> AgentSummary {
+        let agents = self
+            .agents
+            .iter()
+            .map(|info| {
+                let instances: Vec<AgentInstance> = info
+                    .instances
+                    .iter()
+                    .filter(|instance| is_same_project(&instance.cwd, dir))
+                    .cloned()
+                    .collect();
+                AgentInfo {
+                    status: aggregate_status(&instances),
+                    activity: instances.iter().find_map(|instance| instance.activity.clone()),
+                    instances,
+                    ..info.clone()
+                }
+            })
+            .collect();
+        let mut summary = AgentSummary::new(agents, self.total_agents, dir.to_string(), vec![dir.to_string()]);
+        summary.last_updated = self.last_updated.clone();
+        summary
+    }
 }
 
 impl Default for AgentManager {
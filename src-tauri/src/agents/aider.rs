@@ -0,0 +1,57 @@
+use super::aider_history::ChatHistory;
+use super::session_state::SessionStates;
+use super::{is_on_path, program_name, python_entry_point, scan_instances, AgentInstance, AgentMonitor};
+use crate::process::{CpuSampler, ProcessInspector, ProcessSnapshot};
+use crate::projects::Projects;
+use std::sync::Arc;
+
+pub struct AiderMonitor {
+    cpu: CpuSampler,
+    projects: Arc<Projects>,
+    history: SessionStates<ChatHistory>,
+}
+
+impl AiderMonitor {
+    pub fn new(inspector: Arc<dyn ProcessInspector>, projects: Arc<Projects>) -> Self {
+        Self {
+            cpu: CpuSampler::new(inspector),
+            projects,
+            history: SessionStates::new(ChatHistory),
+        }
+    }
+
+    /// Aider runs as a Python interpreter executing the `aider` entry point
+    /// (pip, pipx, uv) or `python -m aider`.
+    fn is_aider_process(&self, processes: &ProcessSnapshot, pid: i32, path: &str) -> bool {
+        if program_name(path) == "aider" {
+            return true;
+        }
+        if !path.contains("python") {
+            return false;
+        }
+        processes.argv(pid).is_some_and(|argv| {
+            python_entry_point(&argv).is_some_and(|entry| entry == "aider" || entry.starts_with("aider.")) &&
+            !argv.iter().any(|arg| arg == "--version")
+        })
+    }
+}
+
+impl AgentMonitor for AiderMonitor {
+    fn get_instances(&self, processes: &ProcessSnapshot) -> Vec<AgentInstance> {
+        let mut instances = scan_instances(processes, &self.cpu, &self.projects, |pid, path| self.is_aider_process(processes, pid, path));
+
+        // The chat history separates a streaming reply from Aider sitting at
+        // its prompt; busy children (lint, test, /run) still count as work.
+        self.history.apply(&mut instances);
+        instances
+    }
+
+    fn get_name(&self) -> &'static str {
+        "Aider"
+    }
+
+    fn is_available(&self) -> bool {
+        // `aider --version` takes seconds to import, so only look it up
+        is_on_path("aider")
+    }
+}
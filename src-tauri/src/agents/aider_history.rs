@@ -0,0 +1,200 @@
+use super::session_state::{read_tail, SessionFile, SessionSource, TranscriptState, STREAMING_GRACE};
+use super::{AgentInstance, AgentStatus};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CHAT_HISTORY: &str = ".aider.chat.history.md";
+const INPUT_HISTORY: &str = ".aider.input.history";
+
+/// Reads where an Aider session stands from `.aider.chat.history.md`, which
+/// Aider appends to as the chat goes:
+///
+/// ```text
+/// # aider chat started at 2025-06-01 10:00:00
+/// > Aider v0.86.1
+///
+/// #### fix the failing test
+///
+/// The assertion compares against the old constant...
+///
+/// > Tokens: 4.1k sent, 312 received.
+/// > Applied edit to tests/test_parser.py
+/// ```
+///
+/// A trailing `####` prompt means the model is still streaming its reply;
+/// Aider's own `>` lines after a reply mean it is back at the input prompt.
+/// `.aider.input.history` is written the moment the user submits input, so
+/// being newer than the chat history also counts as a pending prompt.
+pub struct ChatHistory;
+
+impl SessionSource for ChatHistory {
+    fn sessions(&self, cwd: &str) -> Vec<SessionFile> {
+        history_root(Path::new(cwd))
+            .and_then(|root| SessionFile::stat(root.join(CHAT_HISTORY)))
+            .into_iter()
+            .collect()
+    }
+
+    fn parse(&self, session: &SessionFile) -> Option<TranscriptState> {
+        let input = session.path.with_file_name(INPUT_HISTORY);
+        let input_modified = fs::metadata(input).and_then(|m| m.modified()).ok();
+        if input_modified.is_some_and(|input| input > session.modified) {
+            return Some(TranscriptState::Prompted);
+        }
+
+        let tail = read_tail(&session.path, session.len)?;
+        let last = tail.lines().rev().map(str::trim_end).find(|line| !line.is_empty())?;
+        let state = if last.starts_with("#### ") {
+            TranscriptState::Prompted
+        } else if is_startup_banner(&tail) {
+            TranscriptState::Idle
+        } else if last.starts_with('>') {
+            TranscriptState::Finished
+        } else if session.modified.elapsed().is_ok_and(|age| age < STREAMING_GRACE) {
+            TranscriptState::Responding
+        } else {
+            TranscriptState::Finished
+        };
+        Some(state)
+    }
+
+    /// Every instance in the repo shares one history file, so it only says
+    /// where the instance it was matched to stands.
+    fn unowned_state(&self) -> Option<TranscriptState> {
+        None
+    }
+
+    /// Busy children (lint, test, /run) count as work even after the reply
+    /// was written.
+    fn status(&self, state: &TranscriptState, instance: &AgentInstance) -> AgentStatus {
+        let busy = instance.status == AgentStatus::Processing;
+        match state.to_status(instance) {
+            AgentStatus::Waiting if busy && instance.activity.is_some() => AgentStatus::Processing,
+            status => status,
+        }
+    }
+}
+
+/// Aider keeps its history files in the git root, which may be above `cwd`.
+fn history_root(cwd: &Path) -> Option<PathBuf> {
+    cwd.ancestors()
+        .find(|dir| dir.join(CHAT_HISTORY).is_file() || dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+/// Only the session header and Aider's `>` info lines since the last start,
+/// i.e. nothing has been asked yet.
+fn is_startup_banner(tail: &str) -> bool {
+    tail.rsplit_once("# aider chat started").is_some_and(|(_, session)| {
+        session
+            .lines()
+            .skip(1)
+            .all(|line| line.trim().is_empty() || line.starts_with('>'))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::time::{Duration, SystemTime};
+
+    const BANNER: &str = "\
+# aider chat started at 2025-06-01 10:00:00
+
+> Aider v0.86.1
+> Main model: anthropic/claude-sonnet-4-20250514 with diff edit format
+> Git repo: .git with 42 files
+";
+
+    /// A repo in the temp dir whose chat history holds `chat`, last written
+    /// `age` ago.
+    fn repo(name: &str, chat: &str, age: Duration) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("checka-aider-test-{}-{}", name, std::process::id()));
+        fs::create_dir_all(dir.join(".git")).unwrap();
+        let history = dir.join(CHAT_HISTORY);
+        fs::write(&history, chat).unwrap();
+        File::options()
+            .write(true)
+            .open(&history)
+            .and_then(|file| file.set_modified(SystemTime::now() - age))
+            .unwrap();
+        dir
+    }
+
+    fn parsed(dir: &Path) -> Option<TranscriptState> {
+        let session = SessionFile::stat(dir.join(CHAT_HISTORY)).unwrap();
+        ChatHistory.parse(&session)
+    }
+
+    #[test]
+    fn trailing_prompt_is_being_answered() {
+        let dir = repo("prompt", &format!("{}\n#### fix the failing test\n", BANNER), Duration::from_secs(60));
+        assert_eq!(parsed(&dir), Some(TranscriptState::Prompted));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn info_lines_after_a_reply_end_the_turn() {
+        let chat = format!(
+            "{}\n#### fix the failing test\n\nThe assertion compares against the old constant.\n\n> Tokens: 4.1k sent, 312 received.\n> Applied edit to tests/test_parser.py\n",
+            BANNER
+        );
+        let dir = repo("finished", &chat, Duration::ZERO);
+        assert_eq!(parsed(&dir), Some(TranscriptState::Finished));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reply_is_streaming_until_it_goes_quiet() {
+        let chat = format!("{}\n#### fix the failing test\n\nThe assertion compares\n", BANNER);
+        let dir = repo("streaming", &chat, Duration::ZERO);
+        assert_eq!(parsed(&dir), Some(TranscriptState::Responding));
+        fs::remove_dir_all(&dir).unwrap();
+
+        let dir = repo("quiet", &chat, Duration::from_secs(60));
+        assert_eq!(parsed(&dir), Some(TranscriptState::Finished));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn startup_banner_only_is_idle() {
+        let earlier = "# aider chat started at 2025-05-31 09:00:00\n\n#### add a README\n\nDone.\n\n";
+        let dir = repo("banner", &format!("{}{}", earlier, BANNER), Duration::from_secs(60));
+        assert_eq!(parsed(&dir), Some(TranscriptState::Idle));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn input_newer_than_the_chat_is_a_prompt() {
+        let dir = repo("input", BANNER, Duration::from_secs(60));
+        fs::write(dir.join(INPUT_HISTORY), "\n# 2025-06-01 10:01:00\n+fix the failing test\n").unwrap();
+        assert_eq!(parsed(&dir), Some(TranscriptState::Prompted));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn instances_sharing_the_history_without_owning_it_use_cpu() {
+        use crate::agents::session_state::SessionStates;
+
+        let dir = repo("shared", &format!("{}\n#### fix the failing test\n", BANNER), Duration::from_secs(60));
+        let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs() as i64;
+        let instance = |pid: i32, started_at: i64| AgentInstance {
+            pid,
+            status: AgentStatus::Waiting,
+            cwd: dir.to_string_lossy().into_owned(),
+            started_at: Some(started_at),
+            cpu_percent: 0.0,
+            activity: None,
+            child_started_at: None,
+        };
+        // The second instance started after the history was created and
+        // last written
+        let instances = [instance(1, now - 3600), instance(2, now + 60)];
+
+        let states = SessionStates::new(ChatHistory).states(&instances);
+        assert_eq!(states.get(&1).map(|found| &found.state), Some(&TranscriptState::Prompted));
+        assert!(!states.contains_key(&2));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,186 @@
+use super::session_state::{read_tail, SessionFile, SessionSource, TranscriptState};
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// Reads Claude Code session transcripts (`~/.claude/projects/<encoded-cwd>/*.jsonl`).
+pub struct TranscriptReader {
+    projects_dir: Option<PathBuf>,
+}
+
+impl TranscriptReader {
+    pub fn new() -> Self {
+        let config_dir = std::env::var_os("CLAUDE_CONFIG_DIR")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".claude")));
+        Self {
+            projects_dir: config_dir.map(|dir| dir.join("projects")),
+        }
+    }
+
+    /// Claude Code names a project's transcript directory after its cwd with
+    /// every non-alphanumeric character replaced by `-`.
+    pub fn encode_project_dir(cwd: &str) -> String {
+        cwd.chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect()
+    }
+}
+
+impl Default for TranscriptReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SessionSource for TranscriptReader {
+    fn sessions(&self, cwd: &str) -> Vec<SessionFile> {
+        let Some(projects_dir) = &self.projects_dir else {
+            return vec![];
+        };
+        fs::read_dir(projects_dir.join(Self::encode_project_dir(cwd)))
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? != "jsonl" {
+                    return None;
+                }
+                SessionFile::stat(path)
+            })
+            .collect()
+    }
+
+    fn parse(&self, session: &SessionFile) -> Option<TranscriptState> {
+        last_state(&read_tail(&session.path, session.len)?, session.modified)
+    }
+}
+
+/// Derives the state from the last conversational entry, skipping summaries,
+/// snapshots, meta messages and subagent (sidechain) traffic.
+fn last_state(tail: &str, modified: SystemTime) -> Option<TranscriptState> {
+    tail.lines().rev().find_map(|line| {
+        let entry: Value = serde_json::from_str(line).ok()?;
+        if entry["isSidechain"].as_bool() == Some(true) || entry["isMeta"].as_bool() == Some(true) {
+            return None;
+        }
+        match entry["type"].as_str()? {
+            "user" => Some(user_state(&entry["message"])),
+            "assistant" => Some(assistant_state(&entry["message"], modified)),
+            _ => None,
+        }
+    })
+}
+
+fn user_state(message: &Value) -> TranscriptState {
+    let blocks = content_blocks(message);
+    if blocks.iter().any(|block| block["type"] == "tool_result") {
+        return TranscriptState::ToolResult;
+    }
+    let interrupted = blocks.iter().any(|block| {
+        block["text"]
+            .as_str()
+            .is_some_and(|text| text.starts_with("[Request interrupted by user"))
+    });
+    if interrupted {
+        TranscriptState::Interrupted
+    } else {
+        TranscriptState::Prompted
+    }
+}
+
+fn assistant_state(message: &Value, written: SystemTime) -> TranscriptState {
+    let tool_use = content_blocks(message)
+        .into_iter()
+        .find(|block| block["type"] == "tool_use");
+    if let Some(tool_use) = tool_use {
+        return TranscriptState::ToolPending {
+            tool: tool_use["name"].as_str().unwrap_or("tool").to_string(),
+            since: written,
+        };
+    }
+    match message["stop_reason"].as_str() {
+        Some("end_turn") | Some("stop_sequence") => TranscriptState::Finished,
+        _ => TranscriptState::Responding,
+    }
+}
+
+/// Message content is either a plain string or a list of typed blocks.
+fn content_blocks(message: &Value) -> Vec<Value> {
+    match &message["content"] {
+        Value::Array(blocks) => blocks.clone(),
+        Value::String(text) => vec![serde_json::json!({ "type": "text", "text": text })],
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const PROMPT: &str = r#"{"type":"user","isSidechain":false,"message":{"role":"user","content":"fix the failing test"}}"#;
+    const TOOL_USE: &str = r#"{"type":"assistant","isSidechain":false,"message":{"role":"assistant","content":[{"type":"text","text":"Running the tests."},{"type":"tool_use","id":"toolu_01","name":"Bash","input":{"command":"cargo test"}}],"stop_reason":"tool_use"}}"#;
+    const TOOL_RESULT: &str = r#"{"type":"user","isSidechain":false,"message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"toolu_01","content":"test result: ok"}]}}"#;
+    const END_TURN: &str = r#"{"type":"assistant","isSidechain":false,"message":{"role":"assistant","content":[{"type":"text","text":"All tests pass."}],"stop_reason":"end_turn"}}"#;
+    const INTERRUPTED: &str = r#"{"type":"user","isSidechain":false,"message":{"role":"user","content":[{"type":"text","text":"[Request interrupted by user for tool use]"}]}}"#;
+
+    fn written() -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_750_000_000)
+    }
+
+    fn state(lines: &[&str]) -> Option<TranscriptState> {
+        last_state(&lines.join("\n"), written())
+    }
+
+    #[test]
+    fn pending_tool_use() {
+        assert_eq!(
+            state(&[PROMPT, TOOL_USE]),
+            Some(TranscriptState::ToolPending {
+                tool: "Bash".to_string(),
+                since: written(),
+            })
+        );
+    }
+
+    #[test]
+    fn tool_result_and_end_turn() {
+        assert_eq!(state(&[PROMPT, TOOL_USE, TOOL_RESULT]), Some(TranscriptState::ToolResult));
+        assert_eq!(state(&[PROMPT, TOOL_USE, TOOL_RESULT, END_TURN]), Some(TranscriptState::Finished));
+    }
+
+    #[test]
+    fn interrupted_turn() {
+        assert_eq!(state(&[PROMPT, TOOL_USE, INTERRUPTED]), Some(TranscriptState::Interrupted));
+    }
+
+    #[test]
+    fn skips_sidechain_meta_and_bookkeeping_lines() {
+        let sidechain = r#"{"type":"assistant","isSidechain":true,"message":{"role":"assistant","content":[{"type":"tool_use","name":"Grep","input":{}}]}}"#;
+        let meta = r#"{"type":"user","isMeta":true,"message":{"role":"user","content":"<command-name>/cost</command-name>"}}"#;
+        let summary = r#"{"type":"summary","summary":"Fixing the failing test","leafUuid":"4f2a"}"#;
+        assert_eq!(
+            state(&[PROMPT, END_TURN, sidechain, meta, summary, "not json"]),
+            Some(TranscriptState::Finished)
+        );
+        assert_eq!(state(&[summary, meta]), None);
+    }
+
+    #[test]
+    fn user_entries() {
+        let text = serde_json::json!({ "content": "hello" });
+        assert_eq!(user_state(&text), TranscriptState::Prompted);
+        let interrupted = serde_json::json!({ "content": "[Request interrupted by user]" });
+        assert_eq!(user_state(&interrupted), TranscriptState::Interrupted);
+    }
+
+    #[test]
+    fn assistant_entries() {
+        let streaming = serde_json::json!({ "content": [{ "type": "text", "text": "Looking" }], "stop_reason": null });
+        assert_eq!(assistant_state(&streaming, written()), TranscriptState::Responding);
+        let stopped = serde_json::json!({ "content": "Done.", "stop_reason": "stop_sequence" });
+        assert_eq!(assistant_state(&stopped, written()), TranscriptState::Finished);
+    }
+}
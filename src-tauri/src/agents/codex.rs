@@ -0,0 +1,58 @@
+use super::codex_rollout::RolloutReader;
+use super::session_state::SessionStates;
+use super::{is_on_path, program_name, scan_instances, AgentInstance, AgentMonitor};
+use crate::process::{CpuSampler, ProcessInspector, ProcessSnapshot};
+use crate::projects::Projects;
+use std::sync::Arc;
+
+pub struct CodexMonitor {
+    cpu: CpuSampler,
+    projects: Arc<Projects>,
+    rollouts: SessionStates<RolloutReader>,
+}
+
+impl CodexMonitor {
+    pub fn new(inspector: Arc<dyn ProcessInspector>, projects: Arc<Projects>) -> Self {
+        Self {
+            cpu: CpuSampler::new(inspector),
+            projects,
+            rollouts: SessionStates::new(RolloutReader::new()),
+        }
+    }
+
+    /// The native binary is named `codex` (or `codex-<target-triple>` when
+    /// launched by the npm package's `codex.js`).
+    fn is_codex_process(&self, processes: &ProcessSnapshot, pid: i32, path: &str) -> bool {
+        if path.contains("node") {
+            processes.argv(pid).is_some_and(|argv| {
+                let runs_codex = argv.iter().skip(1).any(|arg| {
+                    arg.contains("@openai/codex") || matches!(program_name(arg), "codex" | "codex.js")
+                });
+                runs_codex && !argv.iter().any(|arg| arg == "--version")
+            })
+        } else {
+            let name = program_name(path);
+            name == "codex" || name.starts_with("codex-")
+        }
+    }
+}
+
+impl AgentMonitor for CodexMonitor {
+    fn get_instances(&self, processes: &ProcessSnapshot) -> Vec<AgentInstance> {
+        let mut instances = scan_instances(processes, &self.cpu, &self.projects, |pid, path| self.is_codex_process(processes, pid, path));
+
+        // The session rollout says whether a turn is running, finished or
+        // stuck on a tool call awaiting approval. CPU is the fallback until
+        // the session writes one.
+        self.rollouts.apply(&mut instances);
+        instances
+    }
+
+    fn get_name(&self) -> &'static str {
+        "Codex CLI"
+    }
+
+    fn is_available(&self) -> bool {
+        is_on_path("codex")
+    }
+}
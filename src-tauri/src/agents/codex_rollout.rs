@@ -0,0 +1,264 @@
+use super::session_state::{read_tail, HeaderCache, SessionFile, SessionSource, TranscriptState};
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Day directories (`sessions/YYYY/MM/DD`) searched for a running session,
+/// newest first.
+const SEARCHED_DAYS: usize = 7;
+
+/// Reads Codex CLI session rollouts (`~/.codex/sessions/YYYY/MM/DD/rollout-*.jsonl`).
+pub struct RolloutReader {
+    sessions_dir: Option<PathBuf>,
+    /// Session cwd from each rollout's first line
+    cwds: HeaderCache<String>,
+}
+
+impl RolloutReader {
+    pub fn new() -> Self {
+        let codex_home = std::env::var_os("CODEX_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".codex")));
+        Self {
+            sessions_dir: codex_home.map(|dir| dir.join("sessions")),
+            cwds: HeaderCache::new(),
+        }
+    }
+}
+
+impl Default for RolloutReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SessionSource for RolloutReader {
+    fn sessions(&self, cwd: &str) -> Vec<SessionFile> {
+        let Some(sessions_dir) = &self.sessions_dir else {
+            return vec![];
+        };
+        let mut rollouts = recent_rollouts(sessions_dir);
+        self.cwds.retain(&rollouts);
+        rollouts.retain(|rollout| self.cwds.get(&rollout.path, read_session_cwd).as_deref() == Some(cwd));
+        rollouts
+    }
+
+    fn parse(&self, session: &SessionFile) -> Option<TranscriptState> {
+        let tail = read_tail(&session.path, session.len)?;
+        Some(last_state(&tail, session.modified).unwrap_or(TranscriptState::Idle))
+    }
+}
+
+fn sorted_subdirs(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+    // Zero-padded names sort chronologically
+    dirs.sort_by(|a, b| b.cmp(a));
+    dirs
+}
+
+fn recent_rollouts(sessions_dir: &Path) -> Vec<SessionFile> {
+    sorted_subdirs(sessions_dir)
+        .iter()
+        .flat_map(|year| sorted_subdirs(year))
+        .flat_map(|month| sorted_subdirs(&month))
+        .take(SEARCHED_DAYS)
+        .flat_map(|day| fs::read_dir(day).into_iter().flatten())
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != "jsonl" {
+                return None;
+            }
+            SessionFile::stat(path)
+        })
+        .collect()
+}
+
+/// The first line holds the session metadata, either bare (older releases) or
+/// as `{"type":"session_meta","payload":{...}}`.
+fn read_session_cwd(path: &Path) -> Option<String> {
+    let mut line = String::new();
+    BufReader::new(File::open(path).ok()?).read_line(&mut line).ok()?;
+    let meta: Value = serde_json::from_str(&line).ok()?;
+    let meta = if meta["type"] == "session_meta" { &meta["payload"] } else { &meta };
+    meta["cwd"].as_str().map(str::to_string)
+}
+
+/// Derives the state from the last turn-related entry. Entries are either
+/// bare response items (older releases) or wrapped as `response_item` /
+/// `event_msg` with a `payload`.
+fn last_state(tail: &str, modified: SystemTime) -> Option<TranscriptState> {
+    tail.lines().rev().find_map(|line| {
+        let entry: Value = serde_json::from_str(line).ok()?;
+        match entry["type"].as_str()? {
+            "response_item" => item_state(&entry["payload"], modified),
+            "event_msg" => event_state(&entry["payload"]),
+            "session_meta" | "turn_context" | "compacted" => None,
+            _ => item_state(&entry, modified),
+        }
+    })
+}
+
+fn item_state(item: &Value, written: SystemTime) -> Option<TranscriptState> {
+    match item["type"].as_str()? {
+        "message" => match item["role"].as_str()? {
+            "user" if is_injected_context(item) => None,
+            "user" => Some(TranscriptState::Prompted),
+            "assistant" => Some(TranscriptState::Responding),
+            _ => None,
+        },
+        "reasoning" | "web_search_call" => Some(TranscriptState::Responding),
+        "function_call" | "custom_tool_call" => Some(TranscriptState::ToolPending {
+            tool: item["name"].as_str().unwrap_or("tool").to_string(),
+            since: written,
+        }),
+        "local_shell_call" => Some(TranscriptState::ToolPending {
+            tool: "shell".to_string(),
+            since: written,
+        }),
+        "function_call_output" | "custom_tool_call_output" => Some(TranscriptState::ToolResult),
+        _ => None,
+    }
+}
+
+fn event_state(event: &Value) -> Option<TranscriptState> {
+    match event["type"].as_str()? {
+        "task_started" | "user_message" => Some(TranscriptState::Prompted),
+        "task_complete" => Some(TranscriptState::Finished),
+        "turn_aborted" => Some(TranscriptState::Interrupted),
+        // Agent messages, reasoning and token counts mirror response items
+        _ => None,
+    }
+}
+
+/// Codex records the environment and AGENTS.md instructions as user messages
+/// when a session starts, before anything was asked.
+fn is_injected_context(message: &Value) -> bool {
+    message["content"].as_array().is_some_and(|blocks| {
+        blocks.iter().all(|block| {
+            block["text"].as_str().is_some_and(|text| {
+                let text = text.trim_start();
+                text.starts_with("<environment_context>") || text.starts_with("<user_instructions>")
+            })
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const META: &str = r#"{"timestamp":"2025-09-01T10:00:00.000Z","type":"session_meta","payload":{"id":"0199a1b2","cwd":"/Users/john/my-web-app","originator":"codex_cli_rs","cli_version":"0.30.0"}}"#;
+    const CONTEXT: &str = r#"{"type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"<environment_context>\n  <cwd>/Users/john/my-web-app</cwd>\n</environment_context>"}]}}"#;
+    const PROMPT: &str = r#"{"type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"fix the failing test"}]}}"#;
+    const TASK_STARTED: &str = r#"{"type":"event_msg","payload":{"type":"task_started","model_context_window":272000}}"#;
+    const REASONING: &str = r#"{"type":"response_item","payload":{"type":"reasoning","summary":[{"type":"summary_text","text":"**Running tests**"}]}}"#;
+    const FUNCTION_CALL: &str = r#"{"type":"response_item","payload":{"type":"function_call","name":"shell","arguments":"{\"command\":[\"cargo\",\"test\"]}","call_id":"call_01"}}"#;
+    const FUNCTION_OUTPUT: &str = r#"{"type":"response_item","payload":{"type":"function_call_output","call_id":"call_01","output":"test result: ok"}}"#;
+    const TOKEN_COUNT: &str = r#"{"type":"event_msg","payload":{"type":"token_count","info":null}}"#;
+    const TASK_COMPLETE: &str = r#"{"type":"event_msg","payload":{"type":"task_complete","last_agent_message":"All tests pass."}}"#;
+    const TURN_ABORTED: &str = r#"{"type":"event_msg","payload":{"type":"turn_aborted","reason":"interrupted"}}"#;
+
+    fn written() -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_750_000_000)
+    }
+
+    fn state(lines: &[&str]) -> Option<TranscriptState> {
+        last_state(&lines.join("\n"), written())
+    }
+
+    #[test]
+    fn turn_progress() {
+        assert_eq!(state(&[META, CONTEXT, PROMPT, TASK_STARTED]), Some(TranscriptState::Prompted));
+        assert_eq!(state(&[META, PROMPT, TASK_STARTED, REASONING]), Some(TranscriptState::Responding));
+        assert_eq!(
+            state(&[META, PROMPT, REASONING, FUNCTION_CALL]),
+            Some(TranscriptState::ToolPending {
+                tool: "shell".to_string(),
+                since: written(),
+            })
+        );
+        assert_eq!(state(&[META, PROMPT, FUNCTION_CALL, FUNCTION_OUTPUT]), Some(TranscriptState::ToolResult));
+        assert_eq!(
+            state(&[META, PROMPT, FUNCTION_CALL, FUNCTION_OUTPUT, TASK_COMPLETE, TOKEN_COUNT]),
+            Some(TranscriptState::Finished)
+        );
+        assert_eq!(state(&[META, PROMPT, FUNCTION_CALL, TURN_ABORTED]), Some(TranscriptState::Interrupted));
+    }
+
+    #[test]
+    fn fresh_session_has_no_turn() {
+        let turn_context = r#"{"type":"turn_context","payload":{"cwd":"/Users/john/my-web-app","model":"gpt-5"}}"#;
+        assert_eq!(state(&[META, CONTEXT, turn_context, "not json"]), None);
+    }
+
+    #[test]
+    fn bare_response_items() {
+        let call = r#"{"type":"local_shell_call","call_id":"call_01","status":"in_progress","action":{"type":"exec","command":["ls"]}}"#;
+        assert_eq!(
+            state(&[PROMPT, call]),
+            Some(TranscriptState::ToolPending {
+                tool: "shell".to_string(),
+                since: written(),
+            })
+        );
+        let reply = r#"{"type":"message","role":"assistant","content":[{"type":"output_text","text":"Done."}]}"#;
+        assert_eq!(state(&[call, reply]), Some(TranscriptState::Responding));
+    }
+
+    #[test]
+    fn item_and_event_states() {
+        let patch = serde_json::json!({ "type": "custom_tool_call", "name": "apply_patch", "input": "*** Begin Patch" });
+        assert_eq!(
+            item_state(&patch, written()),
+            Some(TranscriptState::ToolPending {
+                tool: "apply_patch".to_string(),
+                since: written(),
+            })
+        );
+        let system = serde_json::json!({ "type": "message", "role": "system", "content": [] });
+        assert_eq!(item_state(&system, written()), None);
+
+        assert_eq!(event_state(&serde_json::json!({ "type": "user_message", "message": "go on" })), Some(TranscriptState::Prompted));
+        assert_eq!(event_state(&serde_json::json!({ "type": "agent_message", "message": "Done." })), None);
+    }
+
+    #[test]
+    fn injected_context() {
+        let instructions = serde_json::json!({ "content": [{ "type": "input_text", "text": "\n<user_instructions>\nUse pnpm.\n</user_instructions>" }] });
+        assert!(is_injected_context(&instructions));
+        let mixed = serde_json::json!({ "content": [
+            { "type": "input_text", "text": "<environment_context></environment_context>" },
+            { "type": "input_text", "text": "fix the failing test" },
+        ] });
+        assert!(!is_injected_context(&mixed));
+        assert!(!is_injected_context(&serde_json::json!({ "content": "fix the failing test" })));
+    }
+
+    #[test]
+    fn session_cwd_from_either_header_format() {
+        let dir = std::env::temp_dir().join(format!("checka-rollout-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let wrapped = dir.join("rollout-wrapped.jsonl");
+        fs::write(&wrapped, format!("{}\n{}\n", META, PROMPT)).unwrap();
+        let bare = dir.join("rollout-bare.jsonl");
+        fs::write(&bare, "{\"id\":\"5973b6c0\",\"timestamp\":\"2025-05-01T10:00:00Z\",\"cwd\":\"/Users/john/api\"}\n").unwrap();
+        let empty = dir.join("rollout-empty.jsonl");
+        fs::write(&empty, "").unwrap();
+
+        assert_eq!(read_session_cwd(&wrapped).as_deref(), Some("/Users/john/my-web-app"));
+        assert_eq!(read_session_cwd(&bare).as_deref(), Some("/Users/john/api"));
+        assert_eq!(read_session_cwd(&empty), None);
+        assert_eq!(read_session_cwd(&dir.join("missing.jsonl")), None);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
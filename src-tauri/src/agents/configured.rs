@@ -0,0 +1,378 @@
+use super::{scan_instances, AgentInstance, AgentMonitor, AgentStatus, BUSY_CPU_PERCENT};
+use crate::paths;
+use crate::process::{CpuSampler, ProcessInspector, ProcessSnapshot};
+use crate::projects::Projects;
+use regex::Regex;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+/// One `[[agents]]` entry of `~/.config/checka/agents.toml`:
+///
+/// ```toml
+/// [[agents]]
+/// name = "OpenCode"
+/// executable = "/opencode$"
+/// exclude = ["--version", "--help"]
+/// log_glob = "~/.local/share/opencode/log/*.log"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct AgentDefinition {
+    pub name: String,
+    /// Regex matched against the executable path
+    pub executable: Option<String>,
+    /// Regex matched against the command line (argv joined by spaces)
+    pub argv: Option<String>,
+    /// Regexes on the command line that rule a process out
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// CPU percent over one poll above which the agent counts as busy
+    #[serde(default = "AgentDefinition::default_cpu_threshold")]
+    pub cpu_threshold: f64,
+    /// Files the agent writes while working. Relative patterns start at the
+    /// agent's cwd; `~` and `{cwd}` are expanded and `*` / `?` may appear in
+    /// any component.
+    pub log_glob: Option<String>,
+    /// A log written this recently means the agent is processing
+    #[serde(default = "AgentDefinition::default_log_active_secs")]
+    pub log_active_secs: u64,
+}
+
+impl AgentDefinition {
+    fn default_cpu_threshold() -> f64 {
+        BUSY_CPU_PERCENT
+    }
+
+    fn default_log_active_secs() -> u64 {
+        3
+    }
+
+    pub fn path() -> Option<PathBuf> {
+        Some(paths::config_dir()?.join("agents.toml"))
+    }
+
+    /// Reads the definitions file; a missing file means no custom agents and
+    /// an invalid one is reported and skipped, as with `config.toml`.
+    pub fn load() -> Vec<Self> {
+        #[derive(Deserialize)]
+        struct AgentsFile {
+            #[serde(default)]
+            agents: Vec<AgentDefinition>,
+        }
+
+        let Some(path) = Self::path() else {
+            return vec![];
+        };
+        let Ok(contents) = fs::read_to_string(&path) else {
+            return vec![];
+        };
+        match toml::from_str::<AgentsFile>(&contents) {
+            Ok(file) => file.agents,
+            Err(e) => {
+                eprintln!("checka: ignoring invalid {}: {}", path.display(), e);
+                vec![]
+            }
+        }
+    }
+}
+
+/// Monitors an agent described by an `AgentDefinition`, for tools that have
+/// no built-in monitor.
+pub struct ConfiguredMonitor {
+    name: String,
+    executable: Option<Regex>,
+    argv: Option<Regex>,
+    exclude: Vec<Regex>,
+    cpu_threshold: f64,
+    log_glob: Option<String>,
+    log_active: Duration,
+    cpu: CpuSampler,
+    projects: Arc<Projects>,
+}
+
+impl ConfiguredMonitor {
+    pub fn new(definition: &AgentDefinition, inspector: Arc<dyn ProcessInspector>, projects: Arc<Projects>) -> Result<Self, String> {
+        let compile = |pattern: &str| {
+            Regex::new(pattern).map_err(|e| format!("{}: invalid pattern {:?}: {}", definition.name, pattern, e))
+        };
+        if definition.executable.is_none() && definition.argv.is_none() {
+            return Err(format!("{}: needs `executable` or `argv`", definition.name));
+        }
+
+        Ok(Self {
+            name: definition.name.clone(),
+            executable: definition.executable.as_deref().map(compile).transpose()?,
+            argv: definition.argv.as_deref().map(compile).transpose()?,
+            exclude: definition.exclude.iter().map(|pattern| compile(pattern)).collect::<Result<_, _>>()?,
+            cpu_threshold: definition.cpu_threshold,
+            log_glob: definition.log_glob.clone(),
+            log_active: Duration::from_secs(definition.log_active_secs),
+            cpu: CpuSampler::new(inspector),
+            projects,
+        })
+    }
+
+    /// The executable pattern is checked first since it needs no argv
+    /// lookup; definitions with only `argv` read every process's arguments
+    /// (once per poll, shared with the other monitors).
+    fn is_agent_process(&self, processes: &ProcessSnapshot, pid: i32, path: &str) -> bool {
+        if self.executable.as_ref().is_some_and(|executable| !executable.is_match(path)) {
+            return false;
+        }
+        if self.argv.is_none() && self.exclude.is_empty() {
+            return true;
+        }
+        processes.command_line(pid).is_some_and(|command_line| {
+            self.argv.as_ref().is_none_or(|argv| argv.is_match(&command_line)) &&
+            !self.exclude.iter().any(|exclude| exclude.is_match(&command_line))
+        })
+    }
+
+    /// `log_glob` with `{cwd}` and `~` expanded.
+    fn log_pattern(&self, cwd: &str) -> Option<PathBuf> {
+        let pattern = self.log_glob.as_ref()?.replace("{cwd}", cwd);
+        match pattern.strip_prefix("~/") {
+            Some(rest) => Some(PathBuf::from(std::env::var_os("HOME")?).join(rest)),
+            None => Some(PathBuf::from(pattern)),
+        }
+    }
+
+    /// When a file matching `log_glob` for `cwd` was last written.
+    fn log_written(&self, cwd: &str) -> Option<SystemTime> {
+        let pattern = self.log_pattern(cwd)?;
+        glob(Path::new(cwd), &pattern)
+            .into_iter()
+            .filter_map(|path| fs::metadata(path).and_then(|metadata| metadata.modified()).ok())
+            .max()
+    }
+}
+
+impl AgentMonitor for ConfiguredMonitor {
+    fn get_instances(&self, processes: &ProcessSnapshot) -> Vec<AgentInstance> {
+        let mut instances = scan_instances(processes, &self.cpu, &self.projects, |pid, path| self.is_agent_process(processes, pid, path));
+
+        // A busy child is work regardless; otherwise a recent log write
+        // decides, and the configured CPU threshold when there is no log.
+        for instance in &mut instances {
+            if instance.activity.is_some() {
+                continue;
+            }
+            let busy = match self.log_written(&instance.cwd) {
+                Some(written) => written.elapsed().is_ok_and(|age| age < self.log_active),
+                None => instance.cpu_percent > self.cpu_threshold,
+            };
+            instance.status = if busy { AgentStatus::Processing } else { AgentStatus::Waiting };
+        }
+        instances
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn is_available(&self) -> bool {
+        // Defined by the user, so always listed
+        true
+    }
+}
+
+/// Paths matching `pattern`, resolved against `base` when relative. `*` and
+/// `?` match within a single component.
+fn glob(base: &Path, pattern: &Path) -> Vec<PathBuf> {
+    let mut paths = vec![base.to_path_buf()];
+    for component in pattern.components() {
+        let part = component.as_os_str().to_string_lossy();
+        paths = match component {
+            Component::Normal(_) if part.contains(['*', '?']) => {
+                let Ok(wildcard) = wildcard_regex(&part) else {
+                    return vec![];
+                };
+                paths
+                    .iter()
+                    .flat_map(|dir| fs::read_dir(dir).into_iter().flatten())
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| {
+                        path.file_name()
+                            .is_some_and(|name| wildcard.is_match(&name.to_string_lossy()))
+                    })
+                    .collect()
+            }
+            _ => paths.into_iter().map(|path| path.join(component)).collect(),
+        };
+    }
+    paths
+}
+
+fn wildcard_regex(part: &str) -> Result<Regex, regex::Error> {
+    let mut pattern = String::from("^");
+    for c in part.chars() {
+        match c {
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    Regex::new(&pattern)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::CpuTimes;
+    use std::collections::HashMap;
+
+    /// Answers process queries from a fixed table of `pid -> (exe, argv)`.
+    struct FakeInspector(HashMap<i32, (&'static str, Vec<&'static str>)>);
+
+    impl ProcessInspector for FakeInspector {
+        fn list_pids(&self) -> Vec<i32> {
+            self.0.keys().copied().collect()
+        }
+        fn exe_path(&self, pid: i32) -> Option<String> {
+            self.0.get(&pid).map(|(exe, _)| exe.to_string())
+        }
+        fn argv(&self, pid: i32) -> Option<Vec<String>> {
+            self.0.get(&pid).map(|(_, argv)| argv.iter().map(|arg| arg.to_string()).collect())
+        }
+        fn cwd(&self, _pid: i32) -> Option<String> {
+            None
+        }
+        fn cpu_times(&self, _pid: i32) -> Option<CpuTimes> {
+            None
+        }
+        fn parent_pid(&self, _pid: i32) -> Option<i32> {
+            None
+        }
+        fn start_time(&self, _pid: i32) -> Option<i64> {
+            None
+        }
+    }
+
+    fn definition(toml: &str) -> AgentDefinition {
+        toml::from_str(&format!("name = \"OpenCode\"\n{}", toml)).unwrap()
+    }
+
+    fn monitor(toml: &str) -> ConfiguredMonitor {
+        let inspector: Arc<dyn ProcessInspector> = Arc::new(FakeInspector(HashMap::new()));
+        ConfiguredMonitor::new(&definition(toml), inspector, Arc::new(Projects::new())).unwrap()
+    }
+
+    /// Which of `processes` the definition in `toml` takes for its agent.
+    fn detected(toml: &str, processes: &[(i32, &'static str, Vec<&'static str>)]) -> Vec<i32> {
+        let inspector: Arc<dyn ProcessInspector> = Arc::new(FakeInspector(
+            processes
+                .iter()
+                .map(|(pid, exe, argv)| (*pid, (*exe, argv.clone())))
+                .collect(),
+        ));
+        let monitor = ConfiguredMonitor::new(&definition(toml), inspector.clone(), Arc::new(Projects::new())).unwrap();
+        let snapshot = ProcessSnapshot::take(inspector);
+        let mut pids: Vec<i32> = processes
+            .iter()
+            .filter(|(pid, exe, _)| monitor.is_agent_process(&snapshot, *pid, exe))
+            .map(|(pid, _, _)| *pid)
+            .collect();
+        pids.sort_unstable();
+        pids
+    }
+
+    #[test]
+    fn executable_argv_and_exclude() {
+        let processes = [
+            (1, "/opt/homebrew/bin/opencode", vec!["opencode"]),
+            (2, "/opt/homebrew/bin/opencode", vec!["opencode", "--version"]),
+            (3, "/opt/homebrew/bin/opencode", vec!["opencode", "serve"]),
+            (4, "/opt/homebrew/bin/node", vec!["node", "/opt/homebrew/bin/opencode", "run"]),
+            (5, "/usr/bin/vim", vec!["vim", "opencode.json"]),
+        ];
+        assert_eq!(detected(r#"executable = "/opencode$""#, &processes), vec![1, 2, 3]);
+        assert_eq!(
+            detected("executable = \"/opencode$\"\nexclude = [\"--version\"]", &processes),
+            vec![1, 3]
+        );
+        // Both patterns have to match
+        assert_eq!(
+            detected("executable = \"/opencode$\"\nargv = \" serve\"", &processes),
+            vec![3]
+        );
+    }
+
+    #[test]
+    fn argv_only_definition_with_excludes() {
+        let processes = [
+            (1, "/opt/homebrew/bin/node", vec!["node", "/opt/homebrew/bin/opencode", "run"]),
+            (2, "/opt/homebrew/bin/opencode", vec!["opencode"]),
+            (3, "/opt/homebrew/bin/opencode", vec!["opencode", "--help"]),
+            (4, "/usr/bin/vim", vec!["vim", "opencode.json"]),
+        ];
+        let toml = "argv = \"(^|/)opencode( |$)\"\nexclude = [\"--version\", \"--help\"]";
+        assert_eq!(detected(toml, &processes), vec![1, 2]);
+    }
+
+    #[test]
+    fn needs_a_pattern() {
+        let inspector: Arc<dyn ProcessInspector> = Arc::new(FakeInspector(HashMap::new()));
+        let error = ConfiguredMonitor::new(&definition(""), inspector.clone(), Arc::new(Projects::new())).err();
+        assert_eq!(error.as_deref(), Some("OpenCode: needs `executable` or `argv`"));
+        assert!(ConfiguredMonitor::new(&definition(r#"argv = "(""#), inspector, Arc::new(Projects::new())).is_err());
+    }
+
+    #[test]
+    fn wildcard_components() {
+        let log = wildcard_regex("*.log").unwrap();
+        assert!(log.is_match("opencode.log"));
+        assert!(!log.is_match("opencode.log.1"));
+        assert!(!log.is_match("opencode_log"));
+
+        let numbered = wildcard_regex("run-??.txt").unwrap();
+        assert!(numbered.is_match("run-01.txt"));
+        assert!(!numbered.is_match("run-1.txt"));
+    }
+
+    #[test]
+    fn glob_matches_each_component() {
+        let dir = std::env::temp_dir().join(format!("checka-glob-test-{}", std::process::id()));
+        for file in ["logs/2025-06/a.log", "logs/2025-07/b.log", "logs/2025-07/b.txt", "logs/c.log"] {
+            let path = dir.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+
+        let mut found = glob(&dir, Path::new("logs/*/*.log"));
+        found.sort();
+        assert_eq!(found, [dir.join("logs/2025-06/a.log"), dir.join("logs/2025-07/b.log")]);
+        // Absolute patterns ignore the base
+        assert_eq!(glob(Path::new("/"), &dir.join("logs/2025-0?/b.*")).len(), 2);
+        assert!(glob(&dir, Path::new("missing/*.log")).is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn log_glob_expansion() {
+        let in_home = monitor("executable = \"/opencode$\"\nlog_glob = \"~/.local/share/opencode/log/*.log\"");
+        let home = PathBuf::from(std::env::var_os("HOME").unwrap());
+        assert_eq!(
+            in_home.log_pattern("/Users/john/my-web-app"),
+            Some(home.join(".local/share/opencode/log/*.log"))
+        );
+
+        let in_cwd = monitor("executable = \"/opencode$\"\nlog_glob = \"{cwd}/.opencode/*.log\"");
+        assert_eq!(
+            in_cwd.log_pattern("/Users/john/my-web-app"),
+            Some(PathBuf::from("/Users/john/my-web-app/.opencode/*.log"))
+        );
+
+        let dir = std::env::temp_dir().join(format!("checka-log-glob-test-{}", std::process::id()));
+        fs::create_dir_all(dir.join(".opencode")).unwrap();
+        let cwd = dir.to_string_lossy();
+        assert_eq!(in_cwd.log_written(&cwd), None);
+        fs::write(dir.join(".opencode/session.log"), "").unwrap();
+        assert!(in_cwd.log_written(&cwd).is_some());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,185 @@
+use super::copilot_sessions::SessionReader;
+use super::session_state::SessionStates;
+use super::{is_on_path, program_name, scan_instances, AgentInstance, AgentMonitor};
+use crate::process::{CpuSampler, ProcessInspector, ProcessSnapshot};
+use crate::projects::Projects;
+use std::sync::Arc;
+
+pub struct CopilotCliMonitor {
+    cpu: CpuSampler,
+    projects: Arc<Projects>,
+    sessions: SessionStates<SessionReader>,
+}
+
+impl CopilotCliMonitor {
+    pub fn new(inspector: Arc<dyn ProcessInspector>, projects: Arc<Projects>) -> Self {
+        Self {
+            cpu: CpuSampler::new(inspector),
+            projects,
+            sessions: SessionStates::new(SessionReader::new()),
+        }
+    }
+
+    /// Copilot CLI runs as Node.js executing the `@github/copilot` package,
+    /// as the platform binary named `copilot`, or behind `gh copilot`, which
+    /// downloads and launches the same CLI.
+    fn is_copilot_process(&self, processes: &ProcessSnapshot, pid: i32, path: &str) -> bool {
+        let argv = || processes.argv(pid).unwrap_or_default();
+        let matches = match program_name(path) {
+            "copilot" => true,
+            "gh" => argv().get(1).is_some_and(|command| command == "copilot"),
+            _ if path.contains("node") => argv().iter().skip(1).any(|arg| {
+                arg.contains("@github/copilot") || arg.contains("/gh/copilot/") || program_name(arg) == "copilot"
+            }),
+            _ => false,
+        };
+        matches && !argv().iter().any(|arg| arg == "--version" || arg == "--help")
+    }
+}
+
+impl AgentMonitor for CopilotCliMonitor {
+    fn get_instances(&self, processes: &ProcessSnapshot) -> Vec<AgentInstance> {
+        let mut instances = scan_instances(processes, &self.cpu, &self.projects, |pid, path| self.is_copilot_process(processes, pid, path));
+
+        // Session events tell a running turn from the input prompt; CPU is
+        // the fallback for sessions that haven't written any yet.
+        self.sessions.apply(&mut instances);
+        instances
+    }
+
+    fn get_name(&self) -> &'static str {
+        "GitHub Copilot CLI"
+    }
+
+    fn is_available(&self) -> bool {
+        // `gh copilot` installs the CLI out of PATH; its first run creates
+        // the state directory either way
+        is_on_path("copilot") || self.sessions.source().has_run()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::CpuTimes;
+    use std::collections::HashMap;
+
+    /// Answers process queries from a fixed table of `pid -> (exe, argv)`.
+    struct FakeInspector(HashMap<i32, (&'static str, Vec<&'static str>)>);
+
+    impl ProcessInspector for FakeInspector {
+        fn list_pids(&self) -> Vec<i32> {
+            self.0.keys().copied().collect()
+        }
+        fn exe_path(&self, pid: i32) -> Option<String> {
+            self.0.get(&pid).map(|(exe, _)| exe.to_string())
+        }
+        fn argv(&self, pid: i32) -> Option<Vec<String>> {
+            self.0.get(&pid).map(|(_, argv)| argv.iter().map(|arg| arg.to_string()).collect())
+        }
+        fn cwd(&self, _pid: i32) -> Option<String> {
+            None
+        }
+        fn cpu_times(&self, _pid: i32) -> Option<CpuTimes> {
+            None
+        }
+        fn parent_pid(&self, _pid: i32) -> Option<i32> {
+            None
+        }
+        fn start_time(&self, _pid: i32) -> Option<i64> {
+            None
+        }
+    }
+
+    /// Which of `processes` the monitor takes for Copilot CLI.
+    fn detected(processes: &[(i32, &'static str, Vec<&'static str>)]) -> Vec<i32> {
+        let inspector: Arc<dyn ProcessInspector> = Arc::new(FakeInspector(
+            processes
+                .iter()
+                .map(|(pid, exe, argv)| (*pid, (*exe, argv.clone())))
+                .collect(),
+        ));
+        let monitor = CopilotCliMonitor::new(inspector.clone(), Arc::new(Projects::new()));
+        let snapshot = ProcessSnapshot::take(inspector);
+        let mut pids: Vec<i32> = processes
+            .iter()
+            .filter(|(pid, exe, _)| monitor.is_copilot_process(&snapshot, *pid, exe))
+            .map(|(pid, _, _)| *pid)
+            .collect();
+        pids.sort_unstable();
+        pids
+    }
+
+    #[test]
+    fn node_running_the_copilot_package() {
+        let node = "/opt/homebrew/bin/node";
+        assert_eq!(
+            detected(&[
+                (1, node, vec!["node", "/opt/homebrew/lib/node_modules/@github/copilot/index.js"]),
+                (2, node, vec!["node", "/Users/john/.local/share/gh/copilot/index.js", "--banner"]),
+                (3, node, vec!["node", "/opt/homebrew/bin/copilot"]),
+                (4, node, vec!["node", "/Users/john/my-web-app/node_modules/.bin/vite"]),
+            ]),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn gh_copilot_and_the_native_binary() {
+        assert_eq!(
+            detected(&[
+                (1, "/opt/homebrew/bin/gh", vec!["gh", "copilot"]),
+                (2, "/opt/homebrew/bin/gh", vec!["gh", "pr", "view", "copilot"]),
+                (3, "/Users/john/.local/bin/copilot", vec!["copilot", "--model", "gpt-5"]),
+                (4, "/usr/local/bin/copilot-language-server", vec!["copilot-language-server", "--stdio"]),
+            ]),
+            vec![1, 3]
+        );
+    }
+
+    #[test]
+    fn skips_version_and_help_checks() {
+        assert_eq!(
+            detected(&[
+                (1, "/Users/john/.local/bin/copilot", vec!["copilot", "--version"]),
+                (2, "/opt/homebrew/bin/gh", vec!["gh", "copilot", "--help"]),
+                (3, "/opt/homebrew/bin/node", vec!["node", "/opt/homebrew/lib/node_modules/@github/copilot/index.js", "--version"]),
+            ]),
+            Vec::<i32>::new()
+        );
+    }
+
+    #[test]
+    fn claude_and_gemini_leave_copilot_alone() {
+        use crate::agents::claude_code::ClaudeCodeMonitor;
+        use crate::agents::gemini::GeminiMonitor;
+        use crate::hooks::HookState;
+
+        let processes = [
+            (1, "/opt/homebrew/bin/node", vec!["node", "/opt/homebrew/lib/node_modules/@github/copilot/index.js", "--model", "claude-sonnet-4.5"]),
+            (2, "/opt/homebrew/bin/node", vec!["node", "/opt/homebrew/bin/copilot", "--model", "gemini-2.5-pro"]),
+            (3, "/opt/homebrew/bin/node", vec!["node", "/opt/homebrew/bin/claude"]),
+            (4, "/opt/homebrew/bin/node", vec!["node", "/opt/homebrew/bin/gemini"]),
+        ];
+        assert_eq!(detected(&processes), vec![1, 2]);
+
+        let inspector: Arc<dyn ProcessInspector> = Arc::new(FakeInspector(
+            processes
+                .iter()
+                .map(|(pid, exe, argv)| (*pid, (*exe, argv.clone())))
+                .collect(),
+        ));
+        let snapshot = ProcessSnapshot::take(inspector.clone());
+        let claude = ClaudeCodeMonitor::new(inspector.clone(), Arc::new(Projects::new()), Arc::new(HookState::new()));
+        let gemini = GeminiMonitor::new(inspector, Arc::new(Projects::new()));
+        let claimed = |is_agent: &dyn Fn(i32, &str) -> bool| -> Vec<i32> {
+            processes
+                .iter()
+                .filter(|(pid, exe, _)| is_agent(*pid, exe))
+                .map(|(pid, _, _)| *pid)
+                .collect()
+        };
+        assert_eq!(claimed(&|pid, exe| claude.is_claude_process(&snapshot, pid, exe)), vec![3]);
+        assert_eq!(claimed(&|pid, exe| gemini.is_gemini_process(&snapshot, pid, exe)), vec![4]);
+    }
+}
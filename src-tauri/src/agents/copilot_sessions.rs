@@ -0,0 +1,176 @@
+use super::session_state::{read_tail, HeaderCache, SessionFile, SessionSource, TranscriptState};
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Lines at the start of a session searched for the `session.start` event.
+const HEADER_LINES: usize = 8;
+
+/// Reads GitHub Copilot CLI session events, one JSON event per line, from
+/// `~/.copilot/session-state/` (`<id>.jsonl`, or `<id>/events.jsonl` in
+/// newer releases):
+///
+/// ```text
+/// {"type":"session.start","data":{"context":{"cwd":"/Users/me/app"},...}}
+/// {"type":"user.message","data":{"content":"fix the build"}}
+/// {"type":"assistant.message","data":{"content":"","toolRequests":[{"name":"bash",...}]}}
+/// {"type":"tool.execution_complete","data":{...}}
+/// ```
+pub struct SessionReader {
+    sessions_dir: Option<PathBuf>,
+    /// Session cwd from each file's `session.start` event
+    cwds: HeaderCache<String>,
+}
+
+impl SessionReader {
+    pub fn new() -> Self {
+        // Copilot CLI keeps its state under $XDG_CONFIG_HOME when that is set
+        let config_home = std::env::var_os("XDG_CONFIG_HOME")
+            .map(|dir| PathBuf::from(dir).join(".copilot"))
+            .filter(|dir| dir.is_dir())
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".copilot")));
+        Self {
+            sessions_dir: config_home.map(|dir| dir.join("session-state")),
+            cwds: HeaderCache::new(),
+        }
+    }
+
+    /// Whether Copilot CLI has been run on this machine.
+    pub fn has_run(&self) -> bool {
+        self.sessions_dir
+            .as_ref()
+            .and_then(|dir| dir.parent())
+            .is_some_and(Path::is_dir)
+    }
+}
+
+impl Default for SessionReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SessionSource for SessionReader {
+    fn sessions(&self, cwd: &str) -> Vec<SessionFile> {
+        let Some(sessions_dir) = &self.sessions_dir else {
+            return vec![];
+        };
+        let mut sessions = session_files(sessions_dir);
+        self.cwds.retain(&sessions);
+        sessions.retain(|session| self.cwds.get(&session.path, read_session_cwd).as_deref() == Some(cwd));
+        sessions
+    }
+
+    fn parse(&self, session: &SessionFile) -> Option<TranscriptState> {
+        let tail = read_tail(&session.path, session.len)?;
+        Some(last_state(&tail, session.modified).unwrap_or(TranscriptState::Idle))
+    }
+}
+
+fn session_files(sessions_dir: &Path) -> Vec<SessionFile> {
+    fs::read_dir(sessions_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let path = if path.is_dir() { path.join("events.jsonl") } else { path };
+            if path.extension()? != "jsonl" {
+                return None;
+            }
+            SessionFile::stat(path)
+        })
+        .collect()
+}
+
+fn read_session_cwd(path: &Path) -> Option<String> {
+    BufReader::new(File::open(path).ok()?)
+        .lines()
+        .take(HEADER_LINES)
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str::<Value>(&line).ok())
+        .find(|event| event["type"] == "session.start")
+        .and_then(|event| {
+            let data = &event["data"];
+            data["context"]["cwd"].as_str().or(data["cwd"].as_str()).map(str::to_string)
+        })
+}
+
+/// Derives the state from the last turn-related event.
+fn last_state(tail: &str, modified: SystemTime) -> Option<TranscriptState> {
+    tail.lines().rev().find_map(|line| {
+        let event: Value = serde_json::from_str(line).ok()?;
+        let data = &event["data"];
+        match event["type"].as_str()? {
+            "user.message" => Some(TranscriptState::Prompted),
+            "assistant.turn_start" | "assistant.turn_end" => Some(TranscriptState::Responding),
+            "assistant.message" => Some(match data["toolRequests"].as_array().and_then(|requests| requests.last()) {
+                Some(request) => TranscriptState::ToolPending {
+                    tool: request["name"].as_str().unwrap_or("tool").to_string(),
+                    since: modified,
+                },
+                None => TranscriptState::Responding,
+            }),
+            // An executing tool has been approved, so it counts as running
+            "tool.execution_start" | "tool.execution_complete" => Some(TranscriptState::ToolResult),
+            "session.idle" | "session.error" => Some(TranscriptState::Finished),
+            "abort" => Some(TranscriptState::Interrupted),
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const START: &str = r#"{"type":"session.start","data":{"sessionId":"5c1f","context":{"cwd":"/Users/john/my-web-app"}}}"#;
+    const PROMPT: &str = r#"{"type":"user.message","data":{"content":"fix the build"}}"#;
+    const TURN_START: &str = r#"{"type":"assistant.turn_start","data":{"turnId":"0"}}"#;
+    const TOOL_REQUEST: &str = r#"{"type":"assistant.message","data":{"content":"","toolRequests":[{"toolCallId":"call_1","name":"bash","arguments":{"command":"npm run build"}}]}}"#;
+    const TOOL_START: &str = r#"{"type":"tool.execution_start","data":{"toolCallId":"call_1","toolName":"bash"}}"#;
+    const TOOL_COMPLETE: &str = r#"{"type":"tool.execution_complete","data":{"toolCallId":"call_1","success":true}}"#;
+    const REPLY: &str = r#"{"type":"assistant.message","data":{"content":"The build passes now."}}"#;
+    const IDLE: &str = r#"{"type":"session.idle","data":{}}"#;
+
+    fn written() -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_750_000_000)
+    }
+
+    fn state(lines: &[&str]) -> Option<TranscriptState> {
+        last_state(&lines.join("\n"), written())
+    }
+
+    #[test]
+    fn follows_a_turn() {
+        assert_eq!(state(&[START]), None);
+        assert_eq!(state(&[START, PROMPT]), Some(TranscriptState::Prompted));
+        assert_eq!(state(&[START, PROMPT, TURN_START]), Some(TranscriptState::Responding));
+        assert_eq!(
+            state(&[START, PROMPT, TURN_START, TOOL_REQUEST]),
+            Some(TranscriptState::ToolPending {
+                tool: "bash".to_string(),
+                since: written(),
+            })
+        );
+        assert_eq!(state(&[START, PROMPT, TOOL_REQUEST, TOOL_START]), Some(TranscriptState::ToolResult));
+        assert_eq!(state(&[START, PROMPT, TOOL_REQUEST, TOOL_COMPLETE]), Some(TranscriptState::ToolResult));
+        assert_eq!(state(&[START, PROMPT, TOOL_COMPLETE, REPLY]), Some(TranscriptState::Responding));
+        assert_eq!(state(&[START, PROMPT, TOOL_COMPLETE, REPLY, IDLE]), Some(TranscriptState::Finished));
+    }
+
+    #[test]
+    fn aborted_turn() {
+        let abort = r#"{"type":"abort","data":{"reason":"user initiated"}}"#;
+        assert_eq!(state(&[START, PROMPT, TOOL_REQUEST, abort]), Some(TranscriptState::Interrupted));
+    }
+
+    #[test]
+    fn skips_unrelated_events() {
+        let usage = r#"{"type":"session.usage_info","data":{"tokens":1024}}"#;
+        let truncated = r#"{"type":"assistant.mess"#;
+        assert_eq!(state(&[START, PROMPT, REPLY, IDLE, usage, truncated]), Some(TranscriptState::Finished));
+    }
+}
@@ -0,0 +1,56 @@
+use super::cursor_chats::ChatReader;
+use super::session_state::SessionStates;
+use super::{is_on_path, program_name, scan_instances, AgentInstance, AgentMonitor};
+use crate::process::{CpuSampler, ProcessInspector, ProcessSnapshot};
+use crate::projects::Projects;
+use std::sync::Arc;
+
+pub struct CursorAgentMonitor {
+    cpu: CpuSampler,
+    projects: Arc<Projects>,
+    chats: SessionStates<ChatReader>,
+}
+
+impl CursorAgentMonitor {
+    pub fn new(inspector: Arc<dyn ProcessInspector>, projects: Arc<Projects>) -> Self {
+        Self {
+            cpu: CpuSampler::new(inspector),
+            projects,
+            chats: SessionStates::new(ChatReader::new()),
+        }
+    }
+
+    /// The `cursor-agent` launcher execs the Node.js runtime bundled in
+    /// `~/.local/share/cursor-agent/versions/<version>/` with that
+    /// directory's `index.js`.
+    fn is_cursor_agent_process(&self, processes: &ProcessSnapshot, pid: i32, path: &str) -> bool {
+        if !path.contains("node") {
+            return false;
+        }
+        processes.argv(pid).is_some_and(|argv| {
+            let runs_cursor_agent = path.contains("/cursor-agent/") ||
+                argv.iter().skip(1).any(|arg| arg.contains("/cursor-agent/") || program_name(arg) == "cursor-agent");
+            runs_cursor_agent && !argv.iter().any(|arg| arg == "--version")
+        })
+    }
+}
+
+impl AgentMonitor for CursorAgentMonitor {
+    fn get_instances(&self, processes: &ProcessSnapshot) -> Vec<AgentInstance> {
+        let mut instances = scan_instances(processes, &self.cpu, &self.projects, |pid, path| self.is_cursor_agent_process(processes, pid, path));
+
+        // The chat store records the last message, so a command awaiting
+        // approval reads differently from one that is running. The TUI
+        // redraws on its own, which makes CPU alone a noisy signal.
+        self.chats.apply(&mut instances);
+        instances
+    }
+
+    fn get_name(&self) -> &'static str {
+        "Cursor Agent"
+    }
+
+    fn is_available(&self) -> bool {
+        is_on_path("cursor-agent")
+    }
+}
@@ -0,0 +1,171 @@
+use super::session_state::{SessionFile, SessionSource, TranscriptState};
+use md5::{Digest, Md5};
+use rusqlite::{Connection, OpenFlags};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Newest blobs searched for the last chat message; the rest are tree nodes
+/// and attachments.
+const RECENT_BLOBS: i64 = 64;
+
+/// A running agent holds the database open, so reads wait briefly for its
+/// write lock instead of failing.
+const BUSY_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Reads Cursor Agent chat stores (`~/.cursor/chats/<md5 of cwd>/<chat id>/store.db`).
+///
+/// Each chat is a SQLite database whose `blobs` table holds the messages as
+/// JSON, e.g. `{"role":"assistant","content":[{"type":"tool-call",...}]}`,
+/// alongside binary tree nodes.
+pub struct ChatReader {
+    chats_dir: Option<PathBuf>,
+}
+
+impl ChatReader {
+    pub fn new() -> Self {
+        Self {
+            chats_dir: std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cursor").join("chats")),
+        }
+    }
+
+    pub fn workspace_hash(cwd: &str) -> String {
+        hex::encode(Md5::digest(cwd.as_bytes()))
+    }
+}
+
+impl Default for ChatReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SessionSource for ChatReader {
+    fn sessions(&self, cwd: &str) -> Vec<SessionFile> {
+        let Some(chats_dir) = &self.chats_dir else {
+            return vec![];
+        };
+        fs::read_dir(chats_dir.join(Self::workspace_hash(cwd)))
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| chat_store(&entry.ok()?.path()))
+            .collect()
+    }
+
+    fn parse(&self, session: &SessionFile) -> Option<TranscriptState> {
+        last_state(&session.path, session.modified)
+    }
+}
+
+/// The chat's `store.db`, with the latest write time and combined size of the
+/// database and its write-ahead log.
+fn chat_store(chat_dir: &Path) -> Option<SessionFile> {
+    let mut store = SessionFile::stat(chat_dir.join("store.db"))?;
+    if let Some(wal) = SessionFile::stat(store.path.with_extension("db-wal")) {
+        store.modified = store.modified.max(wal.modified);
+        store.len += wal.len;
+    }
+    Some(store)
+}
+
+fn last_state(store: &Path, written: SystemTime) -> Option<TranscriptState> {
+    let connection = Connection::open_with_flags(
+        store,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .ok()?;
+    connection.busy_timeout(BUSY_TIMEOUT).ok()?;
+    let mut statement = connection
+        .prepare("SELECT data FROM blobs ORDER BY rowid DESC LIMIT ?1")
+        .ok()?;
+    let blobs = statement
+        .query_map([RECENT_BLOBS], |row| row.get::<_, Vec<u8>>(0))
+        .ok()?;
+
+    let state = blobs
+        .filter_map(Result::ok)
+        .filter_map(|blob| serde_json::from_slice::<Value>(&blob).ok())
+        .find_map(|message| message_state(&message, written));
+    Some(state.unwrap_or(TranscriptState::Idle))
+}
+
+fn message_state(message: &Value, written: SystemTime) -> Option<TranscriptState> {
+    match message["role"].as_str()? {
+        "user" if is_injected_context(message) => None,
+        "user" => Some(TranscriptState::Prompted),
+        "tool" => Some(TranscriptState::ToolResult),
+        "assistant" => {
+            let tool_call = message["content"].as_array().and_then(|parts| {
+                parts.iter().rev().find(|part| part["type"] == "tool-call")
+            });
+            Some(match tool_call {
+                Some(call) => TranscriptState::ToolPending {
+                    tool: call["toolName"].as_str().unwrap_or("tool").to_string(),
+                    since: written,
+                },
+                None => TranscriptState::Responding,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Cursor Agent opens each chat with a user message describing the
+/// environment (`<user_info>`), before anything was asked.
+fn is_injected_context(message: &Value) -> bool {
+    let starts_with_context = |text: &str| text.trim_start().starts_with("<user_info>");
+    match &message["content"] {
+        Value::String(text) => starts_with_context(text),
+        Value::Array(parts) => parts
+            .iter()
+            .all(|part| part["text"].as_str().is_some_and(starts_with_context)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const USER_INFO: &str = r#"{"role":"user","content":"<user_info>\nOS Version: darwin 24.5.0\nWorkspace Path: /Users/john/my-web-app\n</user_info>"}"#;
+    const PROMPT: &str = r#"{"role":"user","content":[{"type":"text","text":"fix the failing test"}]}"#;
+    const TOOL_CALL: &str = r#"{"role":"assistant","content":[{"type":"text","text":"Running the tests."},{"type":"tool-call","toolCallId":"tool_01","toolName":"run_terminal_cmd","args":{"command":"npm test"}}]}"#;
+    const TOOL_RESULT: &str = r#"{"role":"tool","content":[{"type":"tool-result","toolCallId":"tool_01","toolName":"run_terminal_cmd","result":"Tests: 12 passed"}]}"#;
+    const REPLY: &str = r#"{"role":"assistant","content":[{"type":"text","text":"All tests pass."}]}"#;
+
+    fn written() -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_750_000_000)
+    }
+
+    fn state(blob: &str) -> Option<TranscriptState> {
+        message_state(&serde_json::from_str(blob).unwrap(), written())
+    }
+
+    #[test]
+    fn message_states() {
+        assert_eq!(state(PROMPT), Some(TranscriptState::Prompted));
+        assert_eq!(
+            state(TOOL_CALL),
+            Some(TranscriptState::ToolPending {
+                tool: "run_terminal_cmd".to_string(),
+                since: written(),
+            })
+        );
+        assert_eq!(state(TOOL_RESULT), Some(TranscriptState::ToolResult));
+        assert_eq!(state(REPLY), Some(TranscriptState::Responding));
+        assert_eq!(state(r#"{"role":"system","content":"You are a coding agent."}"#), None);
+    }
+
+    #[test]
+    fn environment_message_is_not_a_prompt() {
+        assert_eq!(state(USER_INFO), None);
+
+        let parts = r#"{"role":"user","content":[{"type":"text","text":"<user_info>\nOS Version: darwin 24.5.0\n</user_info>"}]}"#;
+        assert!(is_injected_context(&serde_json::from_str(parts).unwrap()));
+        let followed_by_prompt = r#"{"role":"user","content":[{"type":"text","text":"<user_info></user_info>"},{"type":"text","text":"fix the failing test"}]}"#;
+        assert!(!is_injected_context(&serde_json::from_str(followed_by_prompt).unwrap()));
+        assert!(!is_injected_context(&serde_json::from_str(PROMPT).unwrap()));
+    }
+}
@@ -1,35 +1,342 @@
 use serde::{Deserialize, Serialize};
 
+use std::collections::HashMap;
+
+use crate::process::{CpuSampler, ProcessInspector, ProcessSnapshot, ProcessTree};
+use crate::projects::Projects;
+
 pub mod claude_code;
+pub mod claude_transcript;
+pub mod codex;
+pub mod codex_rollout;
+pub mod configured;
+pub mod copilot;
+pub mod copilot_sessions;
+pub mod cursor_agent;
+pub mod cursor_chats;
+pub mod aider;
+pub mod aider_history;
 pub mod gemini;
+pub mod session_state;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AgentStatus {
     Off,
     Processing,
     Waiting,
+    /// Blocked on the user, e.g. a tool permission prompt
+    NeedsInput { reason: String },
     Error(String),
 }
 
+/// One running process of an agent in the monitored project.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentInstance {
+    pub pid: i32,
+    pub status: AgentStatus,
+    pub cwd: String,
+    /// Process start time as seconds since the Unix epoch
+    pub started_at: Option<i64>,
+    pub cpu_percent: f64,
+    /// What the agent's busiest child is doing, e.g. "running: cargo test"
+    pub activity: Option<String>,
+    /// Start time of the newest process the agent spawned (its own helper
+    /// processes aside), as seconds since the Unix epoch
+    #[serde(skip)]
+    pub child_started_at: Option<i64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentInfo {
     pub name: String,
     pub status: AgentStatus,
     pub available: bool,
     pub last_updated: Option<String>,
+    pub instances: Vec<AgentInstance>,
+    pub activity: Option<String>,
 }
 
+/// `processes` is the process table of the current poll, shared by every
+/// monitor.
 pub trait AgentMonitor: Send + Sync {
-    fn get_status(&self) -> AgentStatus;
-    fn get_name(&self) -> &'static str;
+    fn get_instances(&self, processes: &ProcessSnapshot) -> Vec<AgentInstance>;
+    fn get_name(&self) -> &str;
     fn is_available(&self) -> bool;
+
+    fn get_status(&self, processes: &ProcessSnapshot) -> AgentStatus {
+        aggregate_status(&self.get_instances(processes))
+    }
     
-    fn get_info(&self) -> AgentInfo {
+    /// `available` is the result of `is_available`, checked once up front
+    /// since it may spawn the agent's CLI.
+    fn get_info(&self, processes: &ProcessSnapshot, available: bool) -> AgentInfo {
+        let instances = self.get_instances(processes);
         AgentInfo {
             name: self.get_name().to_string(),
-            status: self.get_status(),
-            available: self.is_available(),
+            status: aggregate_status(&instances),
+            available,
             last_updated: Some(chrono::Local::now().format("%H:%M:%S").to_string()),
+            activity: instances.iter().find_map(|instance| instance.activity.clone()),
+            instances,
+        }
+    }
+}
+
+/// Collapses instance states into the agent-level status: an instance waiting
+/// on the user wins, then processing, then errors, then waiting.
+pub fn aggregate_status(instances: &[AgentInstance]) -> AgentStatus {
+    let statuses = || instances.iter().map(|instance| &instance.status);
+    if let Some(needs_input) = statuses().find(|status| matches!(status, AgentStatus::NeedsInput { .. })) {
+        needs_input.clone()
+    } else if statuses().any(|status| matches!(status, AgentStatus::Processing)) {
+        AgentStatus::Processing
+    } else if let Some(error) = statuses().find(|status| matches!(status, AgentStatus::Error(_))) {
+        error.clone()
+    } else if statuses().any(|status| matches!(status, AgentStatus::Waiting)) {
+        AgentStatus::Waiting
+    } else {
+        AgentStatus::Off
+    }
+}
+
+pub fn get_current_dir() -> Option<String> {
+    std::env::current_dir()
+        .ok()
+        .and_then(|p| p.to_str().map(|s| s.to_string()))
+}
+
+pub fn is_same_project(process_cwd: &str, current_dir: &str) -> bool {
+    // In production mode, require exact match
+    if !cfg!(debug_assertions) {
+        return process_cwd == current_dir;
+    }
+
+    // In development mode, allow parent/child directory relationships
+    let process_path = std::path::Path::new(process_cwd);
+    let current_path = std::path::Path::new(current_dir);
+
+    // Check if process_cwd is the parent of current_dir (typical dev scenario)
+    if let Some(parent) = current_path.parent() {
+        if parent == process_path {
+            return true;
+        }
+    }
+
+    // Check if current_dir is the parent of process_cwd
+    if let Some(parent) = process_path.parent() {
+        if parent == current_path {
+            return true;
         }
     }
-}
\ No newline at end of file
+
+    // Exact match
+    process_cwd == current_dir
+}
+
+/// CPU percent over one poll above which a process counts as busy.
+pub const BUSY_CPU_PERCENT: f64 = 1.0;
+
+const SHELLS: &[&str] = &["sh", "bash", "zsh", "fish", "dash"];
+
+/// Maps CPU utilisation over the last poll interval to a status.
+pub fn status_from_cpu(cpu_percent: f64) -> AgentStatus {
+    if cpu_percent > BUSY_CPU_PERCENT {
+        AgentStatus::Processing
+    } else {
+        AgentStatus::Waiting
+    }
+}
+
+/// Finds every process accepted by `is_agent` whose cwd belongs to a monitored
+/// project and samples its CPU. `is_agent` receives the pid and its executable
+/// path, and should read argv through `processes` so it is fetched once per
+/// poll.
+///
+/// Launchers such as `npx claude` show up as a matching parent of the real
+/// agent process, so matches that descend from another match are folded into
+/// their ancestor.
+///
+/// Agents run tools (`cargo test`, `npm install`) as child processes while
+/// they themselves sit idle, so an instance is `Processing` when it or any of
+/// its descendants is busy.
+pub fn scan_instances(
+    processes: &ProcessSnapshot,
+    cpu: &CpuSampler,
+    projects: &Projects,
+    is_agent: impl Fn(i32, &str) -> bool,
+) -> Vec<AgentInstance> {
+    let matches: Vec<(i32, String)> = processes
+        .pids()
+        .iter()
+        .copied()
+        .filter(|&pid| processes.exe_path(pid).is_some_and(|path| is_agent(pid, &path)))
+        .filter_map(|pid| {
+            let cwd = processes.cwd(pid)?;
+            projects.contains(&cwd).then_some((pid, cwd))
+        })
+        .collect();
+
+    if matches.is_empty() {
+        cpu.retain(&[]);
+        return vec![];
+    }
+
+    let tree = processes.tree();
+    let matched_pids: Vec<i32> = matches.iter().map(|(pid, _)| *pid).collect();
+
+    let instances: Vec<(i32, String, Vec<i32>)> = matches
+        .into_iter()
+        .filter(|(pid, _)| !tree.has_ancestor_in(*pid, &matched_pids))
+        .map(|(pid, cwd)| (pid, cwd, tree.descendants(pid)))
+        .collect();
+
+    let sampled: Vec<i32> = instances
+        .iter()
+        .flat_map(|(pid, _, descendants)| std::iter::once(*pid).chain(descendants.iter().copied()))
+        .collect();
+    cpu.retain(&sampled);
+    let usage: HashMap<i32, f64> = sampled
+        .iter()
+        .map(|&pid| (pid, cpu.sample(pid).unwrap_or(0.0)))
+        .collect();
+
+    instances
+        .into_iter()
+        .map(|(pid, cwd, descendants)| {
+            // Includes the agent's own child processes, e.g. the native binary
+            // behind an npm launcher
+            let cpu_percent = std::iter::once(&pid)
+                .chain(descendants.iter().filter(|child| matched_pids.contains(child)))
+                .map(|pid| usage.get(pid).copied().unwrap_or(0.0))
+                .sum();
+            let busy_child = busiest_child(processes, tree, &usage, &matched_pids, pid);
+            let status = match busy_child {
+                Some(_) => AgentStatus::Processing,
+                None => status_from_cpu(cpu_percent),
+            };
+            let child_started_at = descendants
+                .iter()
+                .filter(|child| !matched_pids.contains(child))
+                .filter_map(|&child| processes.start_time(child))
+                .max();
+            AgentInstance {
+                pid,
+                status,
+                cwd,
+                started_at: processes.start_time(pid),
+                cpu_percent,
+                activity: busy_child.map(|command| format!("running: {}", command)),
+                child_started_at,
+            }
+        })
+        .collect()
+}
+
+fn subtree_cpu(tree: &ProcessTree, usage: &HashMap<i32, f64>, pid: i32) -> f64 {
+    usage.get(&pid).copied().unwrap_or(0.0)
+        + tree
+            .descendants(pid)
+            .iter()
+            .map(|child| usage.get(child).copied().unwrap_or(0.0))
+            .sum::<f64>()
+}
+
+/// Follows the busiest branch below `pid` and describes the command doing the
+/// work. Shell wrappers (`zsh -c ...`) and the agent's own processes (an npm
+/// launcher's native binary, in `agent_pids`) are skipped in favour of the
+/// command they run, but deeper helpers (e.g. `rustc` under `cargo`) are not,
+/// since the top-level tool is what the user recognises.
+fn busiest_child(
+    processes: &ProcessSnapshot,
+    tree: &ProcessTree,
+    usage: &HashMap<i32, f64>,
+    agent_pids: &[i32],
+    pid: i32,
+) -> Option<String> {
+    let busiest = |parent: i32| {
+        tree.children(parent)
+            .iter()
+            .map(|&child| (child, subtree_cpu(tree, usage, child)))
+            .filter(|(_, cpu)| *cpu > BUSY_CPU_PERCENT)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(child, _)| child)
+    };
+
+    let mut current = busiest(pid)?;
+    let mut argv = processes.argv(current).unwrap_or_default();
+    let is_wrapper = |pid: i32, argv: &[String]| {
+        agent_pids.contains(&pid) || argv.first().is_some_and(|program| SHELLS.contains(&program_name(program)))
+    };
+    while is_wrapper(current, &argv) {
+        match busiest(current) {
+            Some(child) => {
+                current = child;
+                argv = processes.argv(current).unwrap_or_default();
+            }
+            // The agent's own process is busy, not a tool
+            None if agent_pids.contains(&current) => return None,
+            None => break,
+        }
+    }
+
+    let mut command = argv
+        .iter()
+        .enumerate()
+        .map(|(i, arg)| if i == 0 { program_name(arg) } else { arg.as_str() })
+        .collect::<Vec<_>>()
+        .join(" ");
+    if command.is_empty() {
+        command = processes
+            .exe_path(current)
+            .map(|path| program_name(&path).to_string())
+            .unwrap_or_else(|| current.to_string());
+    }
+    if command.chars().count() > 48 {
+        command = command.chars().take(47).collect::<String>() + "…";
+    }
+    Some(command)
+}
+
+/// The script or `-m` module a Python interpreter runs, e.g. "aider" for
+/// `python3 /usr/local/bin/aider --model sonnet`.
+pub fn python_entry_point(argv: &[String]) -> Option<&str> {
+    let mut args = argv.iter().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-m" => return args.next().map(String::as_str),
+            "-c" => return None,
+            // Options that take a value
+            "-X" | "-W" => {
+                args.next();
+            }
+            option if option.starts_with('-') => {}
+            script => return Some(program_name(script)),
+        }
+    }
+    None
+}
+
+/// Whether `program` is an executable in one of the `$PATH` directories.
+pub fn is_on_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|path| std::env::split_paths(&path).any(|dir| dir.join(program).is_file()))
+}
+
+/// Packages and launchers of the other node-based agents.
+const OTHER_NODE_AGENTS: &[&str] = &["@github/copilot", "/gh/copilot/", "@openai/codex", "/cursor-agent/"];
+
+/// Whether a node process runs Copilot CLI, Codex or Cursor Agent. The Claude
+/// and Gemini node checks match loose substrings of the command line, which
+/// e.g. `copilot --model claude-sonnet-4.5` contains too.
+pub fn runs_other_node_agent(argv: &[String]) -> bool {
+    argv.iter().skip(1).any(|arg| {
+        OTHER_NODE_AGENTS.iter().any(|marker| arg.contains(marker))
+            || matches!(program_name(arg), "copilot" | "codex" | "codex.js" | "cursor-agent")
+    })
+}
+
+fn program_name(program: &str) -> &str {
+    let name = program.rsplit('/').next().unwrap_or(program);
+    // Login shells are reported as "-zsh"
+    name.trim_start_matches('-')
+}
@@ -0,0 +1,486 @@
+//! Session files agents write as a conversation goes (Claude Code
+//! transcripts, Codex rollouts, Aider's chat history, ...) and the turn state
+//! read from them. Each agent only supplies a `SessionSource`; finding the
+//! session of every instance, caching and mapping to a status are shared.
+
+use super::{AgentInstance, AgentStatus};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+/// How much of the end of a session file is read to find the last turn.
+const TAIL_BYTES: u64 = 64 * 1024;
+
+/// A text-only reply written this recently may still be followed by a tool
+/// call, so it is not yet treated as a finished reply.
+pub const STREAMING_GRACE: Duration = Duration::from_secs(3);
+
+/// A tool call left pending this long with nothing running is assumed to be
+/// waiting on a "Do you want to allow ...?" prompt.
+const PERMISSION_PROMPT_DELAY: Duration = Duration::from_secs(5);
+
+/// Tools that legitimately stay pending without child processes or CPU.
+const QUIET_TOOLS: &[&str] = &["Task", "Agent", "WebFetch", "WebSearch"];
+
+/// Where the conversation stands according to the last session entry.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TranscriptState {
+    /// The user sent a prompt and the model hasn't answered yet
+    Prompted,
+    /// The assistant requested a tool that has no result yet
+    ToolPending { tool: String, since: SystemTime },
+    /// A tool finished and the model is reading its output
+    ToolResult,
+    /// The assistant is writing a reply
+    Responding,
+    /// The assistant finished its reply
+    Finished,
+    /// The user interrupted the turn
+    Interrupted,
+    /// The session hasn't written anything since the process started
+    Idle,
+}
+
+impl TranscriptState {
+    /// The status of `instance` in this state. A pending tool is running
+    /// rather than awaiting permission when the agent or its children used
+    /// CPU in the last poll (the CPU-derived `instance.status`), or when a
+    /// child was started since the call, as blocked tools (`sleep`, a
+    /// `git push` waiting on the network) use none.
+    pub fn to_status(&self, instance: &AgentInstance) -> AgentStatus {
+        match self {
+            TranscriptState::ToolPending { tool, since }
+                if instance.status != AgentStatus::Processing
+                    && !started_child_since(instance, *since)
+                    && !QUIET_TOOLS.contains(&tool.as_str())
+                    && since.elapsed().is_ok_and(|pending| pending >= PERMISSION_PROMPT_DELAY) =>
+            {
+                AgentStatus::NeedsInput {
+                    reason: format!("Permission requested for {}", tool),
+                }
+            }
+            TranscriptState::Prompted
+            | TranscriptState::ToolPending { .. }
+            | TranscriptState::ToolResult
+            | TranscriptState::Responding => AgentStatus::Processing,
+            TranscriptState::Finished | TranscriptState::Interrupted | TranscriptState::Idle => {
+                AgentStatus::Waiting
+            }
+        }
+    }
+}
+
+fn started_child_since(instance: &AgentInstance, since: SystemTime) -> bool {
+    let since = unix_secs(since).unwrap_or(i64::MAX);
+    instance.child_started_at.is_some_and(|started| started >= since)
+}
+
+/// One session file on disk.
+#[derive(Debug, Clone)]
+pub struct SessionFile {
+    pub path: PathBuf,
+    /// When the session began, where the file system records it
+    pub created: Option<SystemTime>,
+    pub modified: SystemTime,
+    pub len: u64,
+}
+
+impl SessionFile {
+    pub fn stat(path: PathBuf) -> Option<Self> {
+        let metadata = fs::metadata(&path).ok()?;
+        Some(Self {
+            created: metadata.created().ok(),
+            modified: metadata.modified().ok()?,
+            len: metadata.len(),
+            path,
+        })
+    }
+}
+
+/// When a session began and was last written, for telling which instance it
+/// belongs to.
+pub trait Session {
+    fn created(&self) -> Option<SystemTime>;
+    fn written(&self) -> SystemTime;
+}
+
+impl Session for SessionFile {
+    fn created(&self) -> Option<SystemTime> {
+        self.created
+    }
+
+    fn written(&self) -> SystemTime {
+        self.modified
+    }
+}
+
+/// Pairs instances running in one cwd with their own sessions, by index into
+/// `sessions`, so two agents in the same project don't both report the
+/// newest one.
+///
+/// An agent creates its session once it starts a conversation, so sessions
+/// are taken oldest first and each goes to the instance that started most
+/// recently before it, preferring instances without one yet. An instance that
+/// began several (e.g. after `/clear`) keeps the one written last. Instances
+/// left over, such as resumed sessions appending to an older file, take the
+/// most recently written unclaimed session written since they started.
+pub fn assign<T: Session>(instances: &[&AgentInstance], sessions: &[T]) -> HashMap<i32, usize> {
+    let mut owners: HashMap<i32, usize> = HashMap::new();
+    let mut by_creation: Vec<(usize, i64)> = sessions
+        .iter()
+        .enumerate()
+        .filter_map(|(i, session)| Some((i, unix_secs(session.created()?)?)))
+        .collect();
+    by_creation.sort_by_key(|&(_, created)| created);
+
+    for (i, created) in by_creation {
+        let mut running: Vec<&AgentInstance> = instances
+            .iter()
+            .copied()
+            .filter(|instance| instance.started_at.is_none_or(|started| started <= created))
+            .collect();
+        running.sort_by_key(|instance| Reverse(instance.started_at));
+        let Some(owner) = running
+            .iter()
+            .find(|instance| !owners.contains_key(&instance.pid))
+            .or(running.first())
+        else {
+            continue;
+        };
+        let newer_owned = owners
+            .get(&owner.pid)
+            .is_some_and(|&owned| sessions[owned].written() > sessions[i].written());
+        if !newer_owned {
+            owners.insert(owner.pid, i);
+        }
+    }
+
+    let claimed: HashSet<usize> = owners.values().copied().collect();
+    let mut unclaimed: Vec<usize> = (0..sessions.len()).filter(|i| !claimed.contains(i)).collect();
+    unclaimed.sort_by_key(|&i| Reverse(sessions[i].written()));
+    for instance in instances {
+        if owners.contains_key(&instance.pid) {
+            continue;
+        }
+        let since_start = unclaimed.iter().position(|&i| {
+            instance
+                .started_at
+                .is_none_or(|started| unix_secs(sessions[i].written()).is_some_and(|written| written >= started))
+        });
+        if let Some(position) = since_start {
+            owners.insert(instance.pid, unclaimed.remove(position));
+        }
+    }
+    owners
+}
+
+fn unix_secs(time: SystemTime) -> Option<i64> {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .ok()
+        .map(|since| since.as_secs() as i64)
+}
+
+/// Where an agent keeps its session files and how to read them.
+pub trait SessionSource: Send + Sync {
+    /// Session files that may belong to agents running in `cwd`.
+    fn sessions(&self, cwd: &str) -> Vec<SessionFile>;
+
+    /// The state recorded in `session`, or `None` when it can't be told and
+    /// CPU should decide.
+    fn parse(&self, session: &SessionFile) -> Option<TranscriptState>;
+
+    /// The state of an instance that no session in its cwd belongs to, i.e.
+    /// one started after they were last written. `None` leaves it to CPU.
+    fn unowned_state(&self) -> Option<TranscriptState> {
+        Some(TranscriptState::Idle)
+    }
+
+    /// The status `instance` reports in `state`, where `instance.status` is
+    /// still its CPU reading.
+    fn status(&self, state: &TranscriptState, instance: &AgentInstance) -> AgentStatus {
+        state.to_status(instance)
+    }
+}
+
+/// The session state found for one instance.
+#[derive(Debug, Clone)]
+pub struct SessionMatch {
+    pub state: TranscriptState,
+    /// `None` when the instance hasn't written a session yet
+    pub session: Option<SessionFile>,
+}
+
+struct CachedState {
+    modified: SystemTime,
+    len: u64,
+    state: Option<TranscriptState>,
+}
+
+/// Reads the sessions of an agent's instances through `S`, re-parsing a file
+/// only when it changed.
+pub struct SessionStates<S> {
+    source: S,
+    cache: Mutex<HashMap<PathBuf, CachedState>>,
+}
+
+impl<S: SessionSource> SessionStates<S> {
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    /// Session state of each instance, by pid. Instances whose cwd has no
+    /// session files at all are left out, so CPU decides for them.
+    pub fn states(&self, instances: &[AgentInstance]) -> HashMap<i32, SessionMatch> {
+        let mut cwds: Vec<&str> = instances.iter().map(|instance| instance.cwd.as_str()).collect();
+        cwds.sort_unstable();
+        cwds.dedup();
+
+        let mut listed = HashSet::new();
+        let mut states = HashMap::new();
+        for cwd in cwds {
+            let sessions = self.source.sessions(cwd);
+            if sessions.is_empty() {
+                continue;
+            }
+            listed.extend(sessions.iter().map(|session| session.path.clone()));
+
+            let in_cwd: Vec<&AgentInstance> = instances.iter().filter(|instance| instance.cwd == cwd).collect();
+            let owners = assign(&in_cwd, &sessions);
+            for instance in in_cwd {
+                let found = match owners.get(&instance.pid) {
+                    Some(&i) => self.parse_cached(&sessions[i]).map(|state| SessionMatch {
+                        state,
+                        session: Some(sessions[i].clone()),
+                    }),
+                    None => self.source.unowned_state().map(|state| SessionMatch { state, session: None }),
+                };
+                if let Some(found) = found {
+                    states.insert(instance.pid, found);
+                }
+            }
+        }
+
+        self.cache.lock().unwrap().retain(|path, _| listed.contains(path));
+        states
+    }
+
+    /// Replaces the CPU-derived status of each instance with its session
+    /// state, where there is one. Busy children of an instance that isn't
+    /// processing are background work (dev servers etc.), not turn activity.
+    pub fn apply(&self, instances: &mut [AgentInstance]) {
+        let states = self.states(instances);
+        for instance in instances.iter_mut() {
+            if let Some(found) = states.get(&instance.pid) {
+                instance.status = self.source.status(&found.state, instance);
+            }
+            if instance.status != AgentStatus::Processing {
+                instance.activity = None;
+            }
+        }
+    }
+
+    fn parse_cached(&self, session: &SessionFile) -> Option<TranscriptState> {
+        let mut cache = self.cache.lock().unwrap();
+        if let Some(cached) = cache.get(&session.path) {
+            let unchanged = cached.modified == session.modified && cached.len == session.len;
+            // A reply counts as finished once the grace period passes, even
+            // though the file didn't change
+            if unchanged && cached.state != Some(TranscriptState::Responding) {
+                return cached.state.clone();
+            }
+        }
+
+        let mut state = self.source.parse(session);
+        let recently_written = session.modified.elapsed().is_ok_and(|age| age < STREAMING_GRACE);
+        if state == Some(TranscriptState::Responding) && !recently_written {
+            state = Some(TranscriptState::Finished);
+        }
+
+        cache.insert(
+            session.path.clone(),
+            CachedState {
+                modified: session.modified,
+                len: session.len,
+                state: state.clone(),
+            },
+        );
+        state
+    }
+}
+
+/// Values read once per session file, such as the cwd recorded in its first
+/// line, which never change once written.
+pub struct HeaderCache<T> {
+    values: Mutex<HashMap<PathBuf, T>>,
+}
+
+impl<T: Clone> HeaderCache<T> {
+    pub fn new() -> Self {
+        Self {
+            values: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The cached value for `path`, reading it with `read` the first time.
+    /// Files whose header isn't written yet are read again next time.
+    pub fn get(&self, path: &Path, read: impl FnOnce(&Path) -> Option<T>) -> Option<T> {
+        let mut values = self.values.lock().unwrap();
+        if let Some(value) = values.get(path) {
+            return Some(value.clone());
+        }
+        let value = read(path)?;
+        values.insert(path.to_path_buf(), value.clone());
+        Some(value)
+    }
+
+    /// Forgets every file not in `sessions`, e.g. ones that aged out of the
+    /// searched directories.
+    pub fn retain(&self, sessions: &[SessionFile]) {
+        let listed: HashSet<&Path> = sessions.iter().map(|session| session.path.as_path()).collect();
+        self.values.lock().unwrap().retain(|path, _| listed.contains(path.as_path()));
+    }
+}
+
+impl<T: Clone> Default for HeaderCache<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The last `TAIL_BYTES` of a JSONL file, starting at a line boundary.
+pub fn read_tail(path: &Path, len: u64) -> Option<String> {
+    let mut file = File::open(path).ok()?;
+    let start = len.saturating_sub(TAIL_BYTES);
+    file.seek(SeekFrom::Start(start)).ok()?;
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer).ok()?;
+    let mut tail = String::from_utf8_lossy(&buffer).into_owned();
+    if start > 0 {
+        // Drop the partial first line
+        let first_newline = tail.find('\n').map_or(tail.len(), |i| i + 1);
+        tail.drain(..first_newline);
+    }
+    Some(tail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending(tool: &str, age: Duration) -> TranscriptState {
+        TranscriptState::ToolPending {
+            tool: tool.to_string(),
+            since: SystemTime::now() - age,
+        }
+    }
+
+    /// An instance whose last CPU reading was busy or idle.
+    fn polled(busy: bool) -> AgentInstance {
+        AgentInstance {
+            status: if busy { AgentStatus::Processing } else { AgentStatus::Waiting },
+            ..instance(1, 1_750_000_000)
+        }
+    }
+
+    #[test]
+    fn idle_tool_call_asks_for_permission_after_a_delay() {
+        assert_eq!(pending("Bash", Duration::from_secs(1)).to_status(&polled(false)), AgentStatus::Processing);
+        assert_eq!(
+            pending("Bash", PERMISSION_PROMPT_DELAY).to_status(&polled(false)),
+            AgentStatus::NeedsInput {
+                reason: "Permission requested for Bash".to_string(),
+            }
+        );
+        // Something is running, so it was approved
+        assert_eq!(pending("Bash", PERMISSION_PROMPT_DELAY).to_status(&polled(true)), AgentStatus::Processing);
+    }
+
+    #[test]
+    fn idle_child_started_for_the_tool_means_it_was_approved() {
+        let state = pending("Bash", Duration::from_secs(60));
+        let since = unix_secs(SystemTime::now() - Duration::from_secs(60)).unwrap();
+
+        // e.g. `sleep 30` or a `git push` waiting on the network
+        let blocked = AgentInstance {
+            child_started_at: Some(since + 1),
+            ..polled(false)
+        };
+        assert_eq!(state.to_status(&blocked), AgentStatus::Processing);
+
+        // A dev server from an earlier turn doesn't count
+        let background = AgentInstance {
+            child_started_at: Some(since - 600),
+            ..polled(false)
+        };
+        assert!(matches!(state.to_status(&background), AgentStatus::NeedsInput { .. }));
+    }
+
+    #[test]
+    fn quiet_tools_never_ask_for_permission() {
+        for tool in QUIET_TOOLS {
+            assert_eq!(pending(tool, Duration::from_secs(60)).to_status(&polled(false)), AgentStatus::Processing);
+        }
+    }
+
+    #[test]
+    fn turn_states() {
+        for state in [TranscriptState::Prompted, TranscriptState::ToolResult, TranscriptState::Responding] {
+            assert_eq!(state.to_status(&polled(false)), AgentStatus::Processing);
+        }
+        for state in [TranscriptState::Finished, TranscriptState::Interrupted, TranscriptState::Idle] {
+            assert_eq!(state.to_status(&polled(true)), AgentStatus::Waiting);
+        }
+    }
+
+    fn instance(pid: i32, started_at: i64) -> AgentInstance {
+        AgentInstance {
+            pid,
+            status: AgentStatus::Waiting,
+            cwd: "/Users/john/my-web-app".to_string(),
+            started_at: Some(started_at),
+            cpu_percent: 0.0,
+            activity: None,
+            child_started_at: None,
+        }
+    }
+
+    fn session(created: Option<u64>, written: u64) -> SessionFile {
+        SessionFile {
+            path: PathBuf::from(format!("/tmp/{}-{}.jsonl", created.unwrap_or(0), written)),
+            created: created.map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs)),
+            modified: SystemTime::UNIX_EPOCH + Duration::from_secs(written),
+            len: 0,
+        }
+    }
+
+    #[test]
+    fn each_instance_gets_the_session_it_started() {
+        let (first, second) = (instance(1, 100), instance(2, 200));
+        // The first instance was prompted only after the second started
+        let sessions = [session(Some(210), 260), session(Some(230), 250), session(Some(50), 240)];
+        let owners = assign(&[&first, &second], &sessions);
+        assert_eq!(owners.get(&2), Some(&0));
+        assert_eq!(owners.get(&1), Some(&1));
+    }
+
+    #[test]
+    fn resumed_sessions_are_matched_by_last_write() {
+        let (first, second) = (instance(1, 100), instance(2, 200));
+        let sessions = [session(Some(10), 150), session(Some(20), 220), session(None, 90)];
+        let owners = assign(&[&first, &second], &sessions);
+        assert_eq!(owners.get(&1), Some(&1));
+        // Left with a session last written before it started
+        assert_eq!(owners.get(&2), None);
+    }
+}
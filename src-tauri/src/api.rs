@@ -0,0 +1,169 @@
+//! Opt-in localhost HTTP API so scripts and editor plugins can read agent
+//! status without running their own process scans.
+//!
+//! - `GET /summary` – the latest `AgentSummary`
+//! - `GET /agents/:name` – one `AgentInfo`, matched like `checka wait --agent`
+//! - `GET /events` – Server-Sent Events stream of status transitions
+//! - `GET /metrics` – Prometheus metrics
+
+use std::io::{self, Write};
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use percent_encoding::percent_decode_str;
+use serde::Serialize;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::agent_manager::AgentSummary;
+use crate::cli::agent_matches;
+use crate::config::ApiConfig;
+use crate::metrics::Metrics;
+use crate::transitions::TransitionBus;
+
+/// Comment line sent on idle event streams so proxies and clients notice a
+/// dropped connection.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Starts the server on a background thread when enabled in the config.
+pub fn serve(
+    config: &ApiConfig,
+    summary: Arc<Mutex<Option<AgentSummary>>>,
+    transitions: Arc<TransitionBus>,
+    metrics: Arc<Metrics>,
+) -> io::Result<()> {
+    if !config.enabled {
+        return Ok(());
+    }
+    let server = Server::http(("127.0.0.1", config.port)).map_err(io::Error::other)?;
+    let port = config.port;
+    let allowed_origins = config.allowed_origins.clone();
+
+    std::thread::spawn(move || {
+        for request in server.incoming_requests() {
+            if !is_local_host(&request, port) {
+                let _ = request.respond(error(403, "unexpected Host header"));
+                continue;
+            }
+            if *request.method() != Method::Get {
+                let _ = request.respond(error(405, "method not allowed"));
+                continue;
+            }
+
+            let origin = header(&request, "Origin")
+                .filter(|origin| allowed_origins.iter().any(|allowed| allowed == origin))
+                .map(str::to_string);
+            let respond = |request: Request, response: Response<io::Cursor<Vec<u8>>>| match &origin {
+                Some(origin) => request.respond(
+                    response
+                        .with_header(Header::from_bytes("Access-Control-Allow-Origin", origin.as_bytes()).unwrap())
+                        .with_header(Header::from_bytes("Vary", "Origin").unwrap()),
+                ),
+                None => request.respond(response),
+            };
+
+            let path = request.url().split('?').next().unwrap_or_default().to_string();
+            let result = match path.trim_end_matches('/') {
+                "/summary" => match summary.lock().unwrap().as_ref() {
+                    Some(summary) => respond(request, json(200, summary)),
+                    None => respond(request, error(503, "agents have not been polled yet")),
+                },
+                "/metrics" => {
+                    let body = metrics.render(summary.lock().unwrap().as_ref());
+                    let response = Response::from_string(body)
+                        .with_header(Header::from_bytes("Content-Type", "text/plain; version=0.0.4").unwrap());
+                    respond(request, response)
+                }
+                "/events" => {
+                    let transitions = Arc::clone(&transitions);
+                    let origin = origin.clone();
+                    std::thread::spawn(move || stream_events(request, &transitions, origin.as_deref()));
+                    Ok(())
+                }
+                path => match path.strip_prefix("/agents/") {
+                    Some(name) => {
+                        let name = percent_decode_str(name).decode_utf8_lossy();
+                        let agent = summary.lock().unwrap().as_ref().and_then(|summary| {
+                            summary
+                                .agents
+                                .iter()
+                                .find(|agent| agent_matches(&agent.name, &name))
+                                .cloned()
+                        });
+                        match agent {
+                            Some(agent) => respond(request, json(200, &agent)),
+                            None => respond(request, error(404, &format!("no agent matching '{}'", name))),
+                        }
+                    }
+                    None => respond(request, error(404, "not found")),
+                },
+            };
+            if let Err(e) = result {
+                eprintln!("checka: API response failed: {}", e);
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Writes transitions to the client until it disconnects. `origin` is the
+/// allowed browser origin that made the request, if any.
+fn stream_events(request: Request, transitions: &TransitionBus, origin: Option<&str>) {
+    let receiver = transitions.subscribe();
+    let mut writer = request.into_writer();
+    let mut head = "HTTP/1.1 200 OK\r\n\
+                    Content-Type: text/event-stream\r\n\
+                    Cache-Control: no-cache\r\n\
+                    Connection: keep-alive\r\n"
+        .to_string();
+    if let Some(origin) = origin {
+        head.push_str(&format!("Access-Control-Allow-Origin: {}\r\nVary: Origin\r\n", origin));
+    }
+    head.push_str("\r\n");
+    if writer.write_all(head.as_bytes()).and_then(|_| writer.flush()).is_err() {
+        return;
+    }
+
+    loop {
+        let message = match receiver.recv_timeout(KEEP_ALIVE_INTERVAL) {
+            Ok(transition) => match serde_json::to_string(&transition) {
+                Ok(data) => format!("event: transition\ndata: {}\n\n", data),
+                Err(_) => continue,
+            },
+            Err(RecvTimeoutError::Timeout) => ": keep-alive\n\n".to_string(),
+            Err(RecvTimeoutError::Disconnected) => return,
+        };
+        if writer.write_all(message.as_bytes()).and_then(|_| writer.flush()).is_err() {
+            return;
+        }
+    }
+}
+
+fn json(status: u16, body: &impl Serialize) -> Response<io::Cursor<Vec<u8>>> {
+    let body = serde_json::to_vec(body).unwrap_or_default();
+    Response::from_data(body)
+        .with_status_code(status)
+        .with_header(Header::from_bytes("Content-Type", "application/json").unwrap())
+}
+
+fn error(status: u16, message: &str) -> Response<io::Cursor<Vec<u8>>> {
+    json(status, &serde_json::json!({ "error": message }))
+}
+
+fn header<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv(name))
+        .map(|header| header.value.as_str())
+}
+
+/// Only `127.0.0.1:<port>` and `localhost:<port>` are served, so a web page
+/// can't reach the API through a DNS name rebound to this machine.
+fn is_local_host(request: &Request, port: u16) -> bool {
+    header(request, "Host").is_some_and(|host| {
+        host.rsplit_once(':').is_some_and(|(name, host_port)| {
+            matches!(name, "127.0.0.1" | "localhost") && host_port == port.to_string()
+        })
+    })
+}
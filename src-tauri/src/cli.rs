@@ -0,0 +1,311 @@
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use clap::{Parser, Subcommand};
+
+use crate::agent_manager::{AgentManager, AgentSummary};
+use crate::agents::{get_current_dir, AgentStatus};
+use crate::control::{self, Request};
+use crate::hooks;
+use crate::mcp;
+use crate::transitions::TransitionTracker;
+use crate::tui;
+
+/// CPU is measured as a delta between polls, so one-shot commands poll twice
+/// this far apart.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Parser)]
+#[command(name = "checka", version, about = "Project-specific AI agent monitor")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Forward a Claude Code hook payload from stdin to the running app
+    Hook {
+        /// Print the settings.json snippet that installs checka's hooks instead
+        #[arg(long)]
+        print_settings: bool,
+    },
+    /// Print agent status for the current directory and exit
+    ///
+    /// Exits with 0 when no agent is processing and 1 otherwise.
+    Status {
+        /// Print the summary as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Block until an agent in the current directory finishes processing
+    ///
+    /// Waits for the agent to be seen processing and then go back to waiting
+    /// (or needing input, or exiting). Exits with 0 when it finishes and 2 on
+    /// timeout or error.
+    Wait {
+        /// Only wait for this agent, e.g. "claude" or "Gemini CLI"
+        #[arg(long)]
+        agent: Option<String>,
+        /// Give up after this many seconds
+        #[arg(long)]
+        timeout: Option<u64>,
+        /// Seconds the agent must stay idle before counting as finished
+        #[arg(long, default_value_t = 0)]
+        settle: u64,
+    },
+    /// Stream agent status changes as newline-delimited JSON
+    ///
+    /// Prints one line per instance transition with the timestamp, agent, pid,
+    /// previous and new status, and time spent in the previous status.
+    Watch,
+    /// Interactive terminal dashboard of agents, e.g. for SSH sessions
+    Tui,
+    /// Serve agent status to other agents as an MCP server over stdio
+    ///
+    /// Offers the `list_agents`, `get_agent_status` and `wait_for_agent_idle`
+    /// tools for the current directory.
+    Mcp,
+}
+
+/// Runs a subcommand and returns the process exit code.
+pub fn run(command: Command) -> i32 {
+    match command {
+        Command::Hook { print_settings } => hook(print_settings),
+        Command::Status { json } => status(json),
+        Command::Wait { agent, timeout, settle } => wait(agent, timeout, settle),
+        Command::Watch => watch(),
+        Command::Tui => match tui::run() {
+            Ok(()) => 0,
+            Err(e) => {
+                eprintln!("checka: terminal UI failed: {}", e);
+                2
+            }
+        },
+        Command::Mcp => mcp::run(),
+    }
+}
+
+/// Where subcommands read agent status from: the running app when it already
+/// monitors the current directory, so the CLI doesn't repeat its process
+/// scans, and a local scan otherwise.
+pub enum SummarySource {
+    App {
+        project: String,
+    },
+    Local {
+        manager: AgentManager,
+        /// The last scan; callers polling concurrently (MCP tool calls) reuse it
+        /// rather than sampling CPU over a near-zero interval
+        last: Mutex<Option<(Instant, AgentSummary)>>,
+    },
+}
+
+impl SummarySource {
+    pub fn connect() -> Self {
+        let project = get_current_dir().unwrap_or_default();
+        let app_monitors_project = control::request(&Request::Summary).is_ok_and(|response| {
+            response
+                .summary
+                .is_some_and(|summary| summary.projects.contains(&project))
+        });
+        if app_monitors_project {
+            SummarySource::App { project }
+        } else {
+            SummarySource::Local {
+                manager: AgentManager::new(),
+                last: Mutex::new(None),
+            }
+        }
+    }
+
+    /// A summary with meaningful CPU readings. A local scan has to be sampled
+    /// twice since CPU is measured between polls.
+    pub fn first_summary(&self) -> Option<AgentSummary> {
+        if let SummarySource::Local { .. } = self {
+            self.get_summary();
+            std::thread::sleep(SAMPLE_INTERVAL);
+        }
+        self.get_summary()
+    }
+
+    /// `None` when the app stopped answering.
+    pub fn get_summary(&self) -> Option<AgentSummary> {
+        match self {
+            SummarySource::App { project } => control::request(&Request::Summary)
+                .ok()?
+                .summary
+                .map(|summary| summary.for_project(project)),
+            SummarySource::Local { manager, last } => {
+                let mut last = last.lock().unwrap();
+                if let Some((scanned_at, summary)) = last.as_ref() {
+                    if scanned_at.elapsed() < SAMPLE_INTERVAL / 2 {
+                        return Some(summary.clone());
+                    }
+                }
+                let summary = manager.get_summary();
+                *last = Some((Instant::now(), summary.clone()));
+                Some(summary)
+            }
+        }
+    }
+}
+
+/// Loose agent name match so `--agent claude` selects "Claude Code".
+pub fn agent_matches(name: &str, filter: &str) -> bool {
+    name.to_lowercase().contains(&filter.to_lowercase())
+}
+
+fn status(json: bool) -> i32 {
+    let Some(summary) = SummarySource::connect().first_summary() else {
+        eprintln!("checka: lost connection to the running app");
+        return 2;
+    };
+
+    if json {
+        match serde_json::to_string_pretty(&summary) {
+            Ok(output) => println!("{}", output),
+            Err(e) => {
+                eprintln!("checka: failed to serialize summary: {}", e);
+                return 2;
+            }
+        }
+    } else {
+        print_table(&summary);
+    }
+
+    if summary.processing_count > 0 {
+        1
+    } else {
+        0
+    }
+}
+
+fn wait(agent: Option<String>, timeout: Option<u64>, settle: u64) -> i32 {
+    let source = SummarySource::connect();
+    let deadline = timeout.map(|secs| Instant::now() + Duration::from_secs(secs));
+    let settle = Duration::from_secs(settle);
+
+    let is_selected = |name: &str| agent.as_deref().is_none_or(|filter| agent_matches(name, filter));
+    // Primes CPU sampling, so a busy agent isn't first read as idle (or an
+    // idle one as busy from its lifetime average)
+    let Some(summary) = source.first_summary() else {
+        eprintln!("checka: lost connection to the running app");
+        return 2;
+    };
+    if !summary.agents.iter().any(|info| is_selected(&info.name)) {
+        eprintln!("checka: no monitored agent matches {:?}", agent.unwrap_or_default());
+        return 2;
+    }
+
+    // Per agent: whether it has been seen processing, and since when it's idle
+    let mut progress: HashMap<String, (bool, Option<Instant>)> = HashMap::new();
+    loop {
+        let Some(summary) = source.get_summary() else {
+            eprintln!("checka: lost connection to the running app");
+            return 2;
+        };
+        let now = Instant::now();
+
+        for info in summary.agents.iter().filter(|info| is_selected(&info.name)) {
+            let (seen_processing, idle_since) = progress.entry(info.name.clone()).or_default();
+            if info.status == AgentStatus::Processing {
+                *seen_processing = true;
+                *idle_since = None;
+                continue;
+            }
+            if !*seen_processing {
+                continue;
+            }
+            let idle_since = *idle_since.get_or_insert(now);
+            if now - idle_since >= settle {
+                println!("{}: {}", info.name, status_label(&info.status));
+                return 0;
+            }
+        }
+
+        if deadline.is_some_and(|deadline| now >= deadline) {
+            eprintln!("checka: timed out waiting for agent to finish");
+            return 2;
+        }
+        std::thread::sleep(SAMPLE_INTERVAL);
+    }
+}
+
+fn watch() -> i32 {
+    let source = SummarySource::connect();
+    let mut tracker = TransitionTracker::new();
+
+    let stdout = std::io::stdout();
+    loop {
+        let Some(summary) = source.get_summary() else {
+            eprintln!("checka: lost connection to the running app");
+            return 2;
+        };
+        for transition in tracker.update(&summary) {
+            let Ok(line) = serde_json::to_string(&transition) else {
+                continue;
+            };
+            let mut out = stdout.lock();
+            // The reader (e.g. `head`) went away
+            if writeln!(out, "{}", line).and_then(|_| out.flush()).is_err() {
+                return 0;
+            }
+        }
+        std::thread::sleep(SAMPLE_INTERVAL);
+    }
+}
+
+pub fn status_label(status: &AgentStatus) -> String {
+    match status {
+        AgentStatus::Off => "Off".to_string(),
+        AgentStatus::Processing => "Processing".to_string(),
+        AgentStatus::Waiting => "Waiting".to_string(),
+        AgentStatus::NeedsInput { .. } => "Needs Input".to_string(),
+        AgentStatus::Error(e) => format!("Error: {}", e),
+    }
+}
+
+fn print_table(summary: &AgentSummary) {
+    println!("Project: {}", summary.current_directory);
+    println!();
+    println!("{:<14} {:<12} {:>7} {:>7}  DETAIL", "AGENT", "STATUS", "PID", "CPU");
+
+    for agent in &summary.agents {
+        if agent.instances.is_empty() {
+            println!("{:<14} {:<12}", agent.name, status_label(&agent.status));
+            continue;
+        }
+        for instance in &agent.instances {
+            let detail = match &instance.status {
+                AgentStatus::NeedsInput { reason } => Some(reason.as_str()),
+                _ => instance.activity.as_deref(),
+            };
+            println!(
+                "{:<14} {:<12} {:>7} {:>6.1}%  {}",
+                agent.name,
+                status_label(&instance.status),
+                instance.pid,
+                instance.cpu_percent,
+                detail.unwrap_or(""),
+            );
+        }
+    }
+}
+
+fn hook(print_settings: bool) -> i32 {
+    if print_settings {
+        println!("{}", hooks::settings_snippet());
+        return 0;
+    }
+
+    let mut payload = String::new();
+    if std::io::stdin().read_to_string(&mut payload).is_ok() {
+        // A non-zero exit would surface as a hook error (or block the tool)
+        // inside Claude Code, so a missing tray app is silently ignored
+        let _ = hooks::forward(&payload);
+    }
+    0
+}
@@ -1,31 +1,50 @@
-use std::sync::{Arc, Mutex, Once};
+use std::sync::{Arc, Mutex};
 
-use tauri::{Manager, State};
-use tauri_nspanel::ManagerExt;
+use tauri::State;
 
-use crate::fns::{
-    setup_menubar_panel_listeners, swizzle_to_menubar_panel, update_menubar_appearance,
-};
-use crate::agent_manager::{AgentManager, AgentSummary};
+use crate::agent_manager::AgentSummary;
 
-static INIT: Once = Once::new();
+#[cfg(target_os = "macos")]
+static INIT: std::sync::Once = std::sync::Once::new();
 
 #[tauri::command]
 pub fn init(app_handle: tauri::AppHandle) {
+    #[cfg(target_os = "macos")]
     INIT.call_once(|| {
+        use crate::fns::{setup_menubar_panel_listeners, swizzle_to_menubar_panel, update_menubar_appearance};
+
         swizzle_to_menubar_panel(&app_handle);
 
         update_menubar_appearance(&app_handle);
 
         setup_menubar_panel_listeners(&app_handle);
     });
+
+    // Elsewhere the window stays a plain window
+    #[cfg(not(target_os = "macos"))]
+    let _ = app_handle;
 }
 
 #[tauri::command]
 pub fn show_menubar_panel(app_handle: tauri::AppHandle) {
-    let panel = app_handle.get_webview_panel("main").unwrap();
+    #[cfg(target_os = "macos")]
+    {
+        use tauri_nspanel::ManagerExt;
 
-    panel.show();
+        let panel = app_handle.get_webview_panel("main").unwrap();
+
+        panel.show();
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        use tauri::Manager;
+
+        if let Some(window) = app_handle.get_webview_window("main") {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }
 }
 
 #[tauri::command]
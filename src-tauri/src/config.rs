@@ -0,0 +1,172 @@
+//! User configuration, read from `~/.config/checka/config.toml`.
+//!
+//! Every section is optional; a missing file means all defaults.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use serde::Deserialize;
+
+use crate::paths;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub notifications: NotificationConfig,
+    pub webhooks: Vec<WebhookConfig>,
+    pub commands: CommandsConfig,
+    pub api: ApiConfig,
+}
+
+/// Which status transitions raise a desktop notification.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    pub enabled: bool,
+    /// Processing → Waiting
+    pub on_finished: bool,
+    /// Any status → NeedsInput
+    pub on_needs_input: bool,
+    /// The agent process exited
+    pub on_off: bool,
+    /// Any status → Error
+    pub on_error: bool,
+    /// Minimum seconds between two notifications for the same instance
+    pub debounce_secs: u64,
+    /// Only announce "finished" after processing at least this long, so quick
+    /// replies don't notify
+    pub min_processing_secs: u64,
+    /// Per-agent switch keyed by agent name, e.g. `"Gemini CLI" = false`
+    pub agents: HashMap<String, bool>,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            on_finished: true,
+            on_needs_input: true,
+            on_off: false,
+            on_error: true,
+            debounce_secs: 10,
+            min_processing_secs: 5,
+            agents: HashMap::new(),
+        }
+    }
+}
+
+impl NotificationConfig {
+    pub fn is_agent_enabled(&self, agent: &str) -> bool {
+        self.enabled && self.agents.get(agent).copied().unwrap_or(true)
+    }
+}
+
+/// Shell commands run on status transitions, with the event described in
+/// `CHECKA_*` environment variables.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct CommandsConfig {
+    /// Processing → Waiting
+    pub on_finish: Option<String>,
+    /// Any status → NeedsInput
+    pub on_needs_input: Option<String>,
+    /// Any status → Processing
+    pub on_start: Option<String>,
+}
+
+/// The opt-in localhost HTTP API.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ApiConfig {
+    pub enabled: bool,
+    /// Always bound on 127.0.0.1
+    pub port: u16,
+    /// Web origins allowed to read responses from a browser, e.g.
+    /// `"http://localhost:5173"`; none by default
+    pub allowed_origins: Vec<String>,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 7337,
+            allowed_origins: Vec::new(),
+        }
+    }
+}
+
+/// A URL that receives a JSON POST on every status transition.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Signs each body with HMAC-SHA256 in the `X-Checka-Signature` header
+    pub secret: Option<String>,
+    #[serde(default = "WebhookConfig::default_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Extra attempts after the first one fails
+    #[serde(default = "WebhookConfig::default_retries")]
+    pub retries: u32,
+}
+
+impl WebhookConfig {
+    fn default_timeout_secs() -> u64 {
+        5
+    }
+
+    fn default_retries() -> u32 {
+        3
+    }
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        Some(paths::config_dir()?.join("config.toml"))
+    }
+
+    /// Reads the config file; a missing file is all defaults.
+    pub fn read() -> Result<Self, String> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+        let Ok(contents) = std::fs::read_to_string(&path) else {
+            return Ok(Self::default());
+        };
+        toml::from_str(&contents).map_err(|e| format!("invalid {}: {}", path.display(), e))
+    }
+
+    /// Like `read`, but falls back to defaults when the file is invalid so a
+    /// typo never keeps the app from starting.
+    pub fn load() -> Self {
+        Self::read().unwrap_or_else(|e| {
+            eprintln!("checka: ignoring {}", e);
+            Self::default()
+        })
+    }
+}
+
+/// The config as seen by the background workers, which read it on every
+/// transition so `reload_config` takes effect without a restart.
+pub struct SharedConfig {
+    config: RwLock<Config>,
+}
+
+impl SharedConfig {
+    pub fn load() -> Self {
+        Self {
+            config: RwLock::new(Config::load()),
+        }
+    }
+
+    pub fn get(&self) -> Config {
+        self.config.read().unwrap().clone()
+    }
+
+    /// Re-reads the file, keeping the current config when it is invalid.
+    pub fn reload(&self) -> Result<(), String> {
+        let config = Config::read()?;
+        *self.config.write().unwrap() = config;
+        Ok(())
+    }
+}
@@ -0,0 +1,214 @@
+//! Control socket of the running app (`$XDG_RUNTIME_DIR/checka.sock`).
+//!
+//! Clients write one JSON request per line, e.g. `{"command":"summary"}` or
+//! `{"command":"add_project","path":"/Users/john/api"}`, and read one JSON
+//! response per line. After `subscribe` the connection stays open and every
+//! status transition is written as a line in the `checka watch` format.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::agent_manager::AgentSummary;
+use crate::agents::get_current_dir;
+use crate::config::SharedConfig;
+use crate::paths;
+use crate::projects::Projects;
+use crate::transitions::TransitionBus;
+
+/// How long a client waits for the app to answer one request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum Request {
+    Summary,
+    Subscribe,
+    AddProject { path: String },
+    RemoveProject { path: String },
+    ReloadConfig,
+    Quit,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Response {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<AgentSummary>,
+    /// Monitored projects after `add_project` / `remove_project`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub projects: Option<Vec<String>>,
+}
+
+impl Response {
+    fn ok() -> Self {
+        Self { ok: true, ..Self::default() }
+    }
+
+    fn error(message: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            error: Some(message.into()),
+            ..Self::default()
+        }
+    }
+}
+
+/// What the control socket operates on.
+pub struct Controller {
+    pub summary: Arc<Mutex<Option<AgentSummary>>>,
+    pub projects: Arc<Projects>,
+    pub transitions: Arc<TransitionBus>,
+    pub config: Arc<SharedConfig>,
+    pub quit: Box<dyn Fn() + Send + Sync>,
+}
+
+pub fn socket_path() -> io::Result<PathBuf> {
+    Ok(paths::runtime_dir()?.join("checka.sock"))
+}
+
+/// Starts serving the control socket, one thread per client.
+pub fn listen(controller: Controller) -> io::Result<()> {
+    let path = socket_path()?;
+    // A socket left behind by a previous run would make bind fail
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path)?;
+    let controller = Arc::new(controller);
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+            let controller = Arc::clone(&controller);
+            std::thread::spawn(move || serve_client(stream, &controller));
+        }
+    });
+
+    Ok(())
+}
+
+fn serve_client(stream: UnixStream, controller: &Controller) {
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            return;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let request = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => request,
+            Err(e) => {
+                if write_line(&mut writer, &Response::error(format!("malformed request: {}", e))).is_err() {
+                    return;
+                }
+                continue;
+            }
+        };
+
+        let response = match &request {
+            Request::Summary => match controller.summary.lock().unwrap().clone() {
+                Some(summary) => Response {
+                    summary: Some(summary),
+                    ..Response::ok()
+                },
+                None => Response::error("agents have not been polled yet"),
+            },
+            Request::Subscribe => Response::ok(),
+            Request::AddProject { path } => projects_response(
+                &controller.projects,
+                controller.projects.add(path),
+                || format!("{} is already monitored", path),
+            ),
+            Request::RemoveProject { path } => projects_response(
+                &controller.projects,
+                controller.projects.remove(path),
+                || format!("{} is not monitored", path),
+            ),
+            Request::ReloadConfig => match controller.config.reload() {
+                Ok(()) => Response::ok(),
+                Err(e) => Response::error(e),
+            },
+            Request::Quit => Response::ok(),
+        };
+        if write_line(&mut writer, &response).is_err() {
+            return;
+        }
+
+        match request {
+            Request::Subscribe => {
+                stream_transitions(&mut writer, &controller.transitions);
+                return;
+            }
+            Request::Quit => {
+                (controller.quit)();
+                return;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn projects_response(projects: &Projects, changed: bool, unchanged_error: impl FnOnce() -> String) -> Response {
+    Response {
+        ok: changed,
+        error: (!changed).then(unchanged_error),
+        projects: Some(projects.list()),
+        ..Response::default()
+    }
+}
+
+fn stream_transitions(writer: &mut UnixStream, transitions: &TransitionBus) {
+    for transition in transitions.subscribe() {
+        if write_line(writer, &transition).is_err() {
+            return;
+        }
+    }
+}
+
+fn write_line(writer: &mut UnixStream, value: &impl Serialize) -> io::Result<()> {
+    let mut line = serde_json::to_vec(value).map_err(io::Error::other)?;
+    line.push(b'\n');
+    writer.write_all(&line)
+}
+
+/// Sends one request to the running app. Fails when no app is running.
+pub fn request(request: &Request) -> io::Result<Response> {
+    let mut stream = UnixStream::connect(socket_path()?)?;
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    write_line(&mut stream, request)?;
+
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line)?;
+    serde_json::from_str(&line).map_err(io::Error::other)
+}
+
+/// When an app is already running, asks it to also monitor the current
+/// directory and returns true so this launch exits instead of adding a second
+/// tray icon.
+pub fn hand_off() -> bool {
+    let Some(dir) = get_current_dir() else {
+        return false;
+    };
+    match request(&Request::AddProject { path: dir.clone() }) {
+        Ok(response) if response.ok => {
+            println!("checka is already running; now also monitoring {}", dir);
+            true
+        }
+        Ok(_) => {
+            println!("checka is already running and monitoring {}", dir);
+            true
+        }
+        Err(_) => false,
+    }
+}
@@ -0,0 +1,200 @@
+//! Receives Claude Code hook events so status changes arrive as they happen.
+//!
+//! Claude Code runs `checka hook` for each configured hook with a JSON payload
+//! on stdin; that process forwards the payload over a Unix socket to the tray
+//! app, which records the latest state per session.
+
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, SystemTime};
+
+use serde::Deserialize;
+
+use crate::agents::session_state::Session;
+use crate::agents::AgentStatus;
+use crate::paths;
+
+/// How long a forwarding `checka hook` process may take to send its payload.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+const HOOK_EVENTS: &[&str] = &[
+    "UserPromptSubmit",
+    "PreToolUse",
+    "PostToolUse",
+    "Notification",
+    "Stop",
+    "SessionStart",
+    "SessionEnd",
+];
+
+/// The fields of a Claude Code hook payload that checka uses.
+#[derive(Debug, Clone, Deserialize)]
+pub struct HookEvent {
+    pub session_id: String,
+    pub cwd: String,
+    pub hook_event_name: String,
+    #[serde(default)]
+    pub tool_name: Option<String>,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionState {
+    /// Also the name of the session's transcript, `<id>.jsonl`
+    pub id: String,
+    pub cwd: String,
+    pub status: AgentStatus,
+    pub activity: Option<String>,
+    /// When the first event of the session arrived
+    pub started: SystemTime,
+    pub updated: SystemTime,
+}
+
+impl Session for SessionState {
+    fn created(&self) -> Option<SystemTime> {
+        Some(self.started)
+    }
+
+    fn written(&self) -> SystemTime {
+        self.updated
+    }
+}
+
+/// Latest hook-reported state of every Claude Code session.
+#[derive(Default)]
+pub struct HookState {
+    sessions: Mutex<HashMap<String, SessionState>>,
+    updated: Condvar,
+}
+
+impl HookState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, event: HookEvent) {
+        let mut sessions = self.sessions.lock().unwrap();
+        let (status, activity) = match event.hook_event_name.as_str() {
+            "UserPromptSubmit" | "PostToolUse" => (AgentStatus::Processing, None),
+            "PreToolUse" => (
+                AgentStatus::Processing,
+                event.tool_name.map(|tool| format!("tool: {}", tool)),
+            ),
+            // Claude Code notifies both for permission prompts and for
+            // sitting idle at the prompt for a while
+            "Notification" => match event.message {
+                Some(message) if message.contains("permission") => {
+                    (AgentStatus::NeedsInput { reason: message }, None)
+                }
+                _ => (AgentStatus::Waiting, None),
+            },
+            "Stop" | "SessionStart" => (AgentStatus::Waiting, None),
+            "SessionEnd" => {
+                sessions.remove(&event.session_id);
+                self.updated.notify_all();
+                return;
+            }
+            _ => return,
+        };
+
+        let now = SystemTime::now();
+        let started = sessions.get(&event.session_id).map_or(now, |session| session.started);
+        sessions.insert(
+            event.session_id.clone(),
+            SessionState {
+                id: event.session_id,
+                cwd: event.cwd,
+                status,
+                activity,
+                started,
+                updated: now,
+            },
+        );
+        self.updated.notify_all();
+    }
+
+    /// Sessions running in `cwd`.
+    pub fn sessions_in(&self, cwd: &str) -> Vec<SessionState> {
+        self.sessions
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|session| session.cwd == cwd)
+            .cloned()
+            .collect()
+    }
+
+    /// Blocks until a hook event arrives or `timeout` passes, so pollers can
+    /// refresh immediately instead of on their next tick.
+    pub fn wait_for_update(&self, timeout: Duration) {
+        let sessions = self.sessions.lock().unwrap();
+        let _ = self.updated.wait_timeout(sessions, timeout);
+    }
+}
+
+pub fn socket_path() -> io::Result<PathBuf> {
+    Ok(paths::runtime_dir()?.join("checka-hooks.sock"))
+}
+
+/// Starts accepting forwarded hook payloads on a background thread.
+pub fn listen(state: Arc<HookState>) -> io::Result<()> {
+    let path = socket_path()?;
+    // A socket left behind by a previous run would make bind fail
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path)?;
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+            // One thread per client, so a client that never closes its end
+            // can't hold up other sessions' events
+            let state = Arc::clone(&state);
+            std::thread::spawn(move || receive(stream, &state));
+        }
+    });
+
+    Ok(())
+}
+
+fn receive(mut stream: UnixStream, state: &HookState) {
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+    let mut payload = String::new();
+    if stream.read_to_string(&mut payload).is_err() {
+        return;
+    }
+    match serde_json::from_str::<HookEvent>(&payload) {
+        Ok(event) => state.record(event),
+        Err(e) => eprintln!("checka: ignoring malformed hook payload: {}", e),
+    }
+}
+
+/// Sends a hook payload to the running app.
+pub fn forward(payload: &str) -> io::Result<()> {
+    let mut stream = UnixStream::connect(socket_path()?)?;
+    stream.write_all(payload.as_bytes())
+}
+
+/// The `hooks` section to merge into `~/.claude/settings.json`.
+pub fn settings_snippet() -> String {
+    let command = std::env::current_exe()
+        .map(|exe| format!("{} hook", exe.display()))
+        .unwrap_or_else(|_| "checka hook".to_string());
+    let hook = serde_json::json!([{ "hooks": [{ "type": "command", "command": command }] }]);
+    let tool_hook = serde_json::json!([{ "matcher": "*", "hooks": [{ "type": "command", "command": command }] }]);
+
+    let hooks: serde_json::Map<String, serde_json::Value> = HOOK_EVENTS
+        .iter()
+        .map(|&event| {
+            let entry = if event.ends_with("ToolUse") { &tool_hook } else { &hook };
+            (event.to_string(), entry.clone())
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&serde_json::json!({ "hooks": hooks })).unwrap_or_default()
+}
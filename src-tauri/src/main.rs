@@ -1,36 +1,89 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod api;
+mod cli;
 mod command;
+mod config;
+mod control;
+#[cfg(target_os = "macos")]
 mod fns;
 mod tray;
 mod agents;
 mod agent_manager;
+mod hooks;
+mod mcp;
+mod metrics;
+mod notifications;
+mod paths;
+mod process;
+mod projects;
+mod shell_hooks;
+mod transitions;
+mod tui;
+mod webhooks;
 
+use clap::Parser;
 use tauri::Manager;
+use tauri_plugin_notification::NotificationExt;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use agent_manager::{AgentManager, AgentSummary};
+use transitions::{TransitionBus, TransitionTracker};
 
 #[cfg(target_os = "macos")]
 use window_vibrancy::{apply_vibrancy, NSVisualEffectMaterial};
 
 fn main() {
+    let cli = cli::Cli::parse();
+    if let Some(command) = cli.command {
+        std::process::exit(cli::run(command));
+    }
+    if control::hand_off() {
+        return;
+    }
+
+    let config = Arc::new(config::SharedConfig::load());
     let agent_manager = Arc::new(Mutex::new(AgentManager::new()));
     let agent_summary = Arc::new(Mutex::new(None::<AgentSummary>));
+    let transition_bus = Arc::new(TransitionBus::new());
+    let notification_transitions = transition_bus.subscribe();
+    webhooks::spawn(transition_bus.subscribe(), Arc::clone(&config));
+    shell_hooks::spawn(transition_bus.subscribe(), Arc::clone(&config));
+    let metrics = Arc::new(metrics::Metrics::new());
+    metrics::spawn(transition_bus.subscribe(), Arc::clone(&metrics));
+    let api_config = config.get().api;
+    if let Err(e) = api::serve(&api_config, Arc::clone(&agent_summary), Arc::clone(&transition_bus), metrics) {
+        eprintln!("checka: failed to start the HTTP API on port {}: {}", api_config.port, e);
+    }
 
+    let hook_state = agent_manager.lock().unwrap().hook_state();
+    if let Err(e) = hooks::listen(hook_state.clone()) {
+        eprintln!("checka: failed to listen for Claude Code hooks: {}", e);
+    }
+
+    let projects = agent_manager.lock().unwrap().projects();
     let agent_manager_clone = Arc::clone(&agent_manager);
     let agent_summary_clone = Arc::clone(&agent_summary);
+    let transition_bus_clone = Arc::clone(&transition_bus);
+    let control_summary = Arc::clone(&agent_summary);
 
     std::thread::spawn(move || {
+        let mut tracker = TransitionTracker::new();
         loop {
             let summary = agent_manager_clone.lock().unwrap().get_summary();
+            transition_bus_clone.publish(&tracker.update(&summary));
             *agent_summary_clone.lock().unwrap() = Some(summary);
-            std::thread::sleep(Duration::from_secs(2));
+            // Wake early when a hook reports a state change
+            hook_state.wait_for_update(Duration::from_secs(2));
         }
     });
 
-    tauri::Builder::default()
+    let builder = tauri::Builder::default();
+    #[cfg(target_os = "macos")]
+    let builder = builder.plugin(tauri_nspanel::init());
+
+    builder
         .manage(agent_summary)
         .invoke_handler(tauri::generate_handler![
             command::init,
@@ -40,8 +93,9 @@ fn main() {
             command::has_active_agents,
             command::quit_app
         ])
-        .plugin(tauri_nspanel::init())
-        .setup(|app| {
+        .plugin(tauri_plugin_notification::init())
+        .setup(move |app| {
+            #[cfg(target_os = "macos")]
             app.set_activation_policy(tauri::ActivationPolicy::Accessory);
 
             let app_handle = app.app_handle();
@@ -54,6 +108,28 @@ fn main() {
 
             tray::create(app_handle)?;
 
+            let notifier = app_handle.clone();
+            notifications::spawn(notification_transitions, Arc::clone(&config), move |notification| {
+                let _ = notifier
+                    .notification()
+                    .builder()
+                    .title(&notification.title)
+                    .body(&notification.body)
+                    .show();
+            });
+
+            let quitter = app_handle.clone();
+            let controller = control::Controller {
+                summary: control_summary,
+                projects,
+                transitions: transition_bus,
+                config,
+                quit: Box::new(move || quitter.exit(0)),
+            };
+            if let Err(e) = control::listen(controller) {
+                eprintln!("checka: failed to open the control socket: {}", e);
+            }
+
             Ok(())
         })
         .run(tauri::generate_context!())
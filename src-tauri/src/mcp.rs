@@ -0,0 +1,248 @@
+//! Model Context Protocol server over stdio (`checka mcp`), so an agent can
+//! check what the other agents in its project are doing before touching the
+//! same files.
+//!
+//! Messages are newline-delimited JSON-RPC 2.0. Tool calls run on their own
+//! threads so a long `wait_for_agent_idle` doesn't block other requests.
+
+use std::io::{self, BufRead, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+use crate::agents::{AgentInfo, AgentStatus};
+use crate::cli::{agent_matches, status_label, SummarySource};
+
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const DEFAULT_WAIT_SECS: u64 = 300;
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+type Output = Arc<Mutex<io::Stdout>>;
+
+/// Serves requests from stdin until it closes and returns the exit code.
+pub fn run() -> i32 {
+    let source = Arc::new(SummarySource::connect());
+    // Prime CPU sampling so the first tool call sees real readings
+    source.first_summary();
+
+    let output: Output = Arc::new(Mutex::new(io::stdout()));
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let message: Value = match serde_json::from_str(&line) {
+            Ok(message) => message,
+            Err(e) => {
+                send(&output, error_response(Value::Null, PARSE_ERROR, &e.to_string()));
+                continue;
+            }
+        };
+        // Notifications (no id) such as `notifications/initialized` need no reply
+        let Some(id) = message.get("id").cloned() else {
+            continue;
+        };
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        match method {
+            "initialize" => send(&output, result_response(id, initialize(&params))),
+            "ping" => send(&output, result_response(id, json!({}))),
+            "tools/list" => send(&output, result_response(id, json!({ "tools": tools() }))),
+            "tools/call" => {
+                let source = Arc::clone(&source);
+                let output = Arc::clone(&output);
+                std::thread::spawn(move || {
+                    let response = match call_tool(&source, &params) {
+                        Ok(result) => result_response(id, result),
+                        Err(message) => error_response(id, INVALID_PARAMS, &message),
+                    };
+                    send(&output, response);
+                });
+            }
+            _ => send(&output, error_response(id, METHOD_NOT_FOUND, &format!("unknown method {}", method))),
+        }
+    }
+    0
+}
+
+fn initialize(params: &Value) -> Value {
+    let requested = params["protocolVersion"].as_str().unwrap_or_default();
+    let version = PROTOCOL_VERSIONS
+        .iter()
+        .find(|&&version| version == requested)
+        .unwrap_or(&PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": {} },
+        "serverInfo": { "name": "checka", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "Reports the status of AI coding agents (Claude Code, Gemini CLI, ...) running in this project. \
+                         Check it before editing files another agent may be changing.",
+    })
+}
+
+fn tools() -> Value {
+    let agent = json!({
+        "type": "string",
+        "description": "Agent name or part of it, case-insensitive, e.g. \"gemini\" or \"Claude Code\"",
+    });
+    json!([
+        {
+            "name": "list_agents",
+            "description": "List the AI coding agents running in this project with their status (Processing, Waiting, Needs Input), pids and current activity.",
+            "inputSchema": { "type": "object", "properties": {} },
+        },
+        {
+            "name": "get_agent_status",
+            "description": "Get the status of one agent in this project, including each running instance.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "agent": agent },
+                "required": ["agent"],
+            },
+        },
+        {
+            "name": "wait_for_agent_idle",
+            "description": "Block until an agent in this project is no longer processing, e.g. before editing files it may be changing.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "agent": agent,
+                    "timeout_secs": {
+                        "type": "integer",
+                        "description": format!("Give up after this many seconds (default {})", DEFAULT_WAIT_SECS),
+                    },
+                },
+                "required": ["agent"],
+            },
+        },
+    ])
+}
+
+/// Runs a tool. `Err` is a protocol error; failures the model should see,
+/// like an unknown agent, are returned as an `isError` result instead.
+fn call_tool(source: &SummarySource, params: &Value) -> Result<Value, String> {
+    let arguments = &params["arguments"];
+    let agent_argument = || {
+        arguments["agent"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| "missing string argument 'agent'".to_string())
+    };
+
+    match params["name"].as_str().unwrap_or_default() {
+        "list_agents" => Ok(match source.get_summary() {
+            Some(summary) => text_result(
+                &json!({
+                    "project": summary.current_directory,
+                    "agents": summary.agents.iter().map(describe).collect::<Vec<_>>(),
+                }),
+                false,
+            ),
+            None => lost_connection(),
+        }),
+        "get_agent_status" => {
+            let filter = agent_argument()?;
+            Ok(match find_agent(source, &filter) {
+                Ok(info) => text_result(&describe(&info), false),
+                Err(result) => result,
+            })
+        }
+        "wait_for_agent_idle" => {
+            let filter = agent_argument()?;
+            let timeout = Duration::from_secs(arguments["timeout_secs"].as_u64().unwrap_or(DEFAULT_WAIT_SECS));
+            Ok(wait_for_idle(source, &filter, timeout))
+        }
+        name => Err(format!("unknown tool {}", name)),
+    }
+}
+
+fn wait_for_idle(source: &SummarySource, filter: &str, timeout: Duration) -> Value {
+    let started = Instant::now();
+    loop {
+        let info = match find_agent(source, filter) {
+            Ok(info) => info,
+            Err(result) => return result,
+        };
+        let waited = started.elapsed();
+        if info.status != AgentStatus::Processing {
+            let mut description = describe(&info);
+            description["waited_secs"] = json!(waited.as_secs());
+            return text_result(&description, false);
+        }
+        if waited >= timeout {
+            return text_result(
+                &json!({
+                    "error": format!("{} is still processing after {}s", info.name, waited.as_secs()),
+                    "agent": describe(&info),
+                }),
+                true,
+            );
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn find_agent(source: &SummarySource, filter: &str) -> Result<AgentInfo, Value> {
+    let summary = source.get_summary().ok_or_else(lost_connection)?;
+    summary
+        .agents
+        .into_iter()
+        .find(|info| agent_matches(&info.name, filter))
+        .ok_or_else(|| text_result(&json!({ "error": format!("no monitored agent matches '{}'", filter) }), true))
+}
+
+/// A flatter, model-friendly view of `AgentInfo`.
+fn describe(info: &AgentInfo) -> Value {
+    let reason = |status: &AgentStatus| match status {
+        AgentStatus::NeedsInput { reason } | AgentStatus::Error(reason) => Some(reason.clone()),
+        _ => None,
+    };
+    json!({
+        "name": info.name,
+        "status": status_label(&info.status),
+        "reason": reason(&info.status),
+        "activity": info.activity,
+        "instances": info.instances.iter().map(|instance| json!({
+            "pid": instance.pid,
+            "status": status_label(&instance.status),
+            "cwd": instance.cwd,
+            "cpu_percent": (instance.cpu_percent * 10.0).round() / 10.0,
+            "activity": instance.activity,
+        })).collect::<Vec<_>>(),
+    })
+}
+
+fn text_result(value: &Value, is_error: bool) -> Value {
+    json!({
+        "content": [{ "type": "text", "text": serde_json::to_string_pretty(value).unwrap_or_default() }],
+        "isError": is_error,
+    })
+}
+
+fn lost_connection() -> Value {
+    text_result(&json!({ "error": "lost connection to the running checka app" }), true)
+}
+
+fn result_response(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn send(output: &Output, message: Value) {
+    let mut out = output.lock().unwrap();
+    // stdout closing means the client is gone; the stdin loop ends on its own
+    let _ = writeln!(out, "{}", message).and_then(|_| out.flush());
+}
@@ -0,0 +1,139 @@
+//! Prometheus text exposition of agent activity, served at `/metrics` by the
+//! HTTP API.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
+
+use crate::agent_manager::AgentSummary;
+use crate::agents::AgentStatus;
+use crate::transitions::Transition;
+
+const STATES: [&str; 5] = ["off", "processing", "waiting", "needs_input", "error"];
+
+/// Counters accumulated from transitions since the app started.
+#[derive(Default)]
+pub struct Metrics {
+    /// (agent, project, from, to) → count
+    transitions: Mutex<BTreeMap<(String, String, String, String), u64>>,
+    /// (agent, project) → seconds
+    processing_seconds: Mutex<BTreeMap<(String, String), f64>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, transition: &Transition) {
+        let from = state_label(&transition.previous);
+        let to = state_label(&transition.status);
+        *self
+            .transitions
+            .lock()
+            .unwrap()
+            .entry((transition.agent.clone(), transition.cwd.clone(), from.to_string(), to.to_string()))
+            .or_default() += 1;
+
+        // Processing time is counted once the stretch ends
+        if transition.previous == AgentStatus::Processing {
+            *self
+                .processing_seconds
+                .lock()
+                .unwrap()
+                .entry((transition.agent.clone(), transition.cwd.clone()))
+                .or_default() += transition.previous_duration_secs;
+        }
+    }
+
+    /// Gauges from the latest summary plus the transition counters.
+    pub fn render(&self, summary: Option<&AgentSummary>) -> String {
+        let mut out = String::new();
+
+        header(&mut out, "checka_agent_status", "gauge", "1 for the current state of an agent in a project");
+        let mut instances = Vec::new();
+        let mut cpu = Vec::new();
+        if let Some(summary) = summary {
+            for project in &summary.projects {
+                for agent in summary.for_project(project).agents {
+                    let current = state_label(&agent.status);
+                    for state in STATES {
+                        sample(
+                            &mut out,
+                            "checka_agent_status",
+                            &[("agent", &agent.name), ("project", project), ("state", state)],
+                            if state == current { 1.0 } else { 0.0 },
+                        );
+                    }
+                    let labels = (agent.name.clone(), project.clone());
+                    instances.push((labels.clone(), agent.instances.len() as f64));
+                    cpu.push((labels, agent.instances.iter().map(|instance| instance.cpu_percent).sum::<f64>()));
+                }
+            }
+        }
+
+        header(&mut out, "checka_agent_instances", "gauge", "Running processes of an agent in a project");
+        for ((agent, project), value) in &instances {
+            sample(&mut out, "checka_agent_instances", &[("agent", agent), ("project", project)], *value);
+        }
+
+        header(&mut out, "checka_agent_cpu_percent", "gauge", "CPU used by the agent processes over the last poll");
+        for ((agent, project), value) in &cpu {
+            sample(&mut out, "checka_agent_cpu_percent", &[("agent", agent), ("project", project)], *value);
+        }
+
+        header(&mut out, "checka_transitions_total", "counter", "Agent status changes");
+        for ((agent, project, from, to), count) in self.transitions.lock().unwrap().iter() {
+            sample(
+                &mut out,
+                "checka_transitions_total",
+                &[("agent", agent), ("project", project), ("from", from), ("to", to)],
+                *count as f64,
+            );
+        }
+
+        header(&mut out, "checka_processing_seconds_total", "counter", "Time agents spent processing, counted when each stretch ends");
+        for ((agent, project), seconds) in self.processing_seconds.lock().unwrap().iter() {
+            sample(&mut out, "checka_processing_seconds_total", &[("agent", agent), ("project", project)], *seconds);
+        }
+
+        out
+    }
+}
+
+/// Feeds transitions into `metrics` on a background thread.
+pub fn spawn(transitions: Receiver<Transition>, metrics: Arc<Metrics>) {
+    std::thread::spawn(move || {
+        for transition in transitions {
+            metrics.record(&transition);
+        }
+    });
+}
+
+fn state_label(status: &AgentStatus) -> &'static str {
+    match status {
+        AgentStatus::Off => "off",
+        AgentStatus::Processing => "processing",
+        AgentStatus::Waiting => "waiting",
+        AgentStatus::NeedsInput { .. } => "needs_input",
+        AgentStatus::Error(_) => "error",
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: f64) {
+    let labels: Vec<String> = labels
+        .iter()
+        .map(|(key, value)| format!("{}=\"{}\"", key, escape(value)))
+        .collect();
+    let _ = writeln!(out, "{}{{{}}} {}", name, labels.join(","), value);
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
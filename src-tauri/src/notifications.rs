@@ -0,0 +1,104 @@
+//! Turns status transitions into desktop notifications according to the
+//! `[notifications]` rules in the config.
+
+use std::collections::HashMap;
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::agents::AgentStatus;
+use crate::config::SharedConfig;
+use crate::transitions::Transition;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub title: String,
+    pub body: String,
+}
+
+pub struct NotificationRules {
+    config: Arc<SharedConfig>,
+    last_sent: HashMap<(String, i32), Instant>,
+}
+
+impl NotificationRules {
+    pub fn new(config: Arc<SharedConfig>) -> Self {
+        Self {
+            config,
+            last_sent: HashMap::new(),
+        }
+    }
+
+    /// The notification `transition` should raise, if any.
+    pub fn evaluate(&mut self, transition: &Transition) -> Option<Notification> {
+        let config = self.config.get().notifications;
+        if !config.is_agent_enabled(&transition.agent) {
+            return None;
+        }
+
+        let project = transition.project();
+        let notification = match (&transition.previous, &transition.status) {
+            (AgentStatus::Processing, AgentStatus::Waiting)
+                if config.on_finished
+                    && transition.previous_duration_secs >= config.min_processing_secs as f64 =>
+            {
+                Notification {
+                    title: format!("{} finished", transition.agent),
+                    body: format!(
+                        "{} · worked for {}",
+                        project,
+                        format_duration(transition.previous_duration_secs)
+                    ),
+                }
+            }
+            (_, AgentStatus::NeedsInput { reason }) if config.on_needs_input => Notification {
+                title: format!("{} needs input", transition.agent),
+                body: format!("{} · {}", project, reason),
+            },
+            (previous, AgentStatus::Off) if config.on_off && *previous != AgentStatus::Off => Notification {
+                title: format!("{} exited", transition.agent),
+                body: project.to_string(),
+            },
+            (_, AgentStatus::Error(error)) if config.on_error => Notification {
+                title: format!("{} error", transition.agent),
+                body: format!("{} · {}", project, error),
+            },
+            _ => return None,
+        };
+
+        let key = (transition.agent.clone(), transition.pid);
+        let debounce = Duration::from_secs(config.debounce_secs);
+        let now = Instant::now();
+        if self.last_sent.get(&key).is_some_and(|sent| now - *sent < debounce) {
+            return None;
+        }
+        self.last_sent.insert(key, now);
+        Some(notification)
+    }
+}
+
+/// Evaluates transitions on a background thread and hands the resulting
+/// notifications to `deliver`.
+pub fn spawn(
+    transitions: Receiver<Transition>,
+    config: Arc<SharedConfig>,
+    deliver: impl Fn(&Notification) + Send + 'static,
+) {
+    std::thread::spawn(move || {
+        let mut rules = NotificationRules::new(config);
+        for transition in transitions {
+            if let Some(notification) = rules.evaluate(&transition) {
+                deliver(&notification);
+            }
+        }
+    });
+}
+
+fn format_duration(secs: f64) -> String {
+    let secs = secs.round() as u64;
+    if secs < 60 {
+        format!("{}s", secs)
+    } else {
+        format!("{}m {}s", secs / 60, secs % 60)
+    }
+}
@@ -0,0 +1,39 @@
+use std::fs::{self, DirBuilder};
+use std::io;
+use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+use std::path::PathBuf;
+
+/// Per-user directory for sockets: `$XDG_RUNTIME_DIR` when set, otherwise a
+/// private `checka-<uid>` directory under the system temp dir.
+///
+/// The temp dir is shared and the name predictable, so an existing directory
+/// is only used if this user owns it and nobody else can access it.
+pub fn runtime_dir() -> io::Result<PathBuf> {
+    if let Some(dir) = std::env::var_os("XDG_RUNTIME_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+
+    let uid = unsafe { libc::getuid() };
+    let dir = std::env::temp_dir().join(format!("checka-{}", uid));
+    match DirBuilder::new().mode(0o700).create(&dir) {
+        Err(e) if e.kind() != io::ErrorKind::AlreadyExists => return Err(e),
+        _ => {}
+    }
+
+    let metadata = fs::symlink_metadata(&dir)?;
+    if !metadata.is_dir() || metadata.uid() != uid || metadata.mode() & 0o077 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} is not a private directory owned by this user", dir.display()),
+        ));
+    }
+    Ok(dir)
+}
+
+/// `$XDG_CONFIG_HOME/checka`, defaulting to `~/.config/checka`.
+pub fn config_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("checka"))
+}
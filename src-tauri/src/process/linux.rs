@@ -0,0 +1,107 @@
+use super::{CpuTimes, ProcessInspector};
+use std::fs;
+use std::time::Duration;
+
+/// Reads process information straight from procfs, without spawning helpers.
+pub struct LinuxProcessInspector {
+    clock_ticks: u64,
+}
+
+impl LinuxProcessInspector {
+    pub fn new() -> Self {
+        let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+        Self {
+            clock_ticks: if ticks > 0 { ticks as u64 } else { 100 },
+        }
+    }
+
+    /// Returns the fields of `/proc/<pid>/stat` that follow the `(comm)` entry,
+    /// so index 0 is the process state (field 3 in `proc(5)`).
+    fn stat_fields(pid: i32) -> Option<Vec<String>> {
+        let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+        // comm may itself contain spaces or parentheses, so split after the last ')'
+        let (_, rest) = stat.rsplit_once(')')?;
+        Some(rest.split_whitespace().map(str::to_string).collect())
+    }
+
+    fn ticks_to_duration(&self, ticks: u64) -> Duration {
+        Duration::from_secs_f64(ticks as f64 / self.clock_ticks as f64)
+    }
+
+    fn boot_time() -> Option<i64> {
+        let stat = fs::read_to_string("/proc/stat").ok()?;
+        stat.lines()
+            .find_map(|line| line.strip_prefix("btime "))
+            .and_then(|btime| btime.trim().parse().ok())
+    }
+
+    /// Process start as seconds since boot.
+    fn start_since_boot(&self, fields: &[String]) -> Option<f64> {
+        let ticks: u64 = fields.get(19)?.parse().ok()?;
+        Some(ticks as f64 / self.clock_ticks as f64)
+    }
+}
+
+impl Default for LinuxProcessInspector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProcessInspector for LinuxProcessInspector {
+    fn list_pids(&self) -> Vec<i32> {
+        let Ok(entries) = fs::read_dir("/proc") else {
+            return vec![];
+        };
+        entries
+            .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse::<i32>().ok())
+            .filter(|&pid| pid > 0)
+            .collect()
+    }
+
+    fn exe_path(&self, pid: i32) -> Option<String> {
+        let path = fs::read_link(format!("/proc/{}/exe", pid)).ok()?;
+        let path = path.to_string_lossy();
+        // The binary may have been replaced by an upgrade while the agent keeps running
+        Some(path.trim_end_matches(" (deleted)").to_string())
+    }
+
+    fn argv(&self, pid: i32) -> Option<Vec<String>> {
+        let cmdline = fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+        if cmdline.is_empty() {
+            // Kernel threads and zombies have no command line
+            return None;
+        }
+        Some(
+            cmdline
+                .split(|&b| b == 0)
+                .filter(|arg| !arg.is_empty())
+                .map(|arg| String::from_utf8_lossy(arg).into_owned())
+                .collect(),
+        )
+    }
+
+    fn cwd(&self, pid: i32) -> Option<String> {
+        let path = fs::read_link(format!("/proc/{}/cwd", pid)).ok()?;
+        Some(path.to_string_lossy().into_owned())
+    }
+
+    fn cpu_times(&self, pid: i32) -> Option<CpuTimes> {
+        let fields = Self::stat_fields(pid)?;
+        let utime: u64 = fields.get(11)?.parse().ok()?;
+        let stime: u64 = fields.get(12)?.parse().ok()?;
+        Some(CpuTimes {
+            user: self.ticks_to_duration(utime),
+            system: self.ticks_to_duration(stime),
+        })
+    }
+
+    fn parent_pid(&self, pid: i32) -> Option<i32> {
+        Self::stat_fields(pid)?.get(1)?.parse().ok()
+    }
+
+    fn start_time(&self, pid: i32) -> Option<i64> {
+        let fields = Self::stat_fields(pid)?;
+        Some(Self::boot_time()? + self.start_since_boot(&fields)? as i64)
+    }
+}
@@ -0,0 +1,235 @@
+use super::{CpuTimes, ProcessInspector};
+use libc::{c_int, c_void};
+use std::ffi::CStr;
+use std::mem;
+use std::os::raw::c_char;
+use std::ptr;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+const PROC_ALL_PIDS: u32 = 1;
+const PROC_PIDPATHINFO_MAXSIZE: usize = 4096;
+
+const PROC_PIDTBSDINFO: c_int = 3;
+const PROC_PIDTASKINFO: c_int = 4;
+const PROC_PIDVNODEPATHINFO: c_int = 9;
+
+const MAXCOMLEN: usize = 16;
+const MAXPATHLEN: usize = 1024;
+
+extern "C" {
+    fn proc_listpids(p_type: u32, typeinfo: u32, buffer: *mut c_void, buffersize: c_int) -> c_int;
+    fn proc_pidpath(pid: c_int, buffer: *mut c_char, buffersize: u32) -> c_int;
+    fn proc_pidinfo(pid: c_int, flavor: c_int, arg: u64, buffer: *mut c_void, buffersize: c_int) -> c_int;
+    fn mach_timebase_info(info: *mut MachTimebaseInfo) -> c_int;
+}
+
+// Mirrors of the structs in <sys/proc_info.h>; only a few fields are read
+
+#[repr(C)]
+#[derive(Default)]
+struct MachTimebaseInfo {
+    numer: u32,
+    denom: u32,
+}
+
+#[repr(C)]
+#[allow(dead_code)]
+struct ProcBsdInfo {
+    pbi_flags: u32,
+    pbi_status: u32,
+    pbi_xstatus: u32,
+    pbi_pid: u32,
+    pbi_ppid: u32,
+    pbi_uid: u32,
+    pbi_gid: u32,
+    pbi_ruid: u32,
+    pbi_rgid: u32,
+    pbi_svuid: u32,
+    pbi_svgid: u32,
+    rfu_1: u32,
+    pbi_comm: [c_char; MAXCOMLEN],
+    pbi_name: [c_char; 2 * MAXCOMLEN],
+    pbi_nfiles: u32,
+    pbi_pgid: u32,
+    pbi_pjobc: u32,
+    e_tdev: u32,
+    e_tpgid: u32,
+    pbi_nice: i32,
+    pbi_start_tvsec: u64,
+    pbi_start_tvusec: u64,
+}
+
+#[repr(C)]
+#[allow(dead_code)]
+struct ProcTaskInfo {
+    pti_virtual_size: u64,
+    pti_resident_size: u64,
+    pti_total_user: u64,
+    pti_total_system: u64,
+    pti_threads_user: u64,
+    pti_threads_system: u64,
+    pti_policy: i32,
+    pti_faults: i32,
+    pti_pageins: i32,
+    pti_cow_faults: i32,
+    pti_messages_sent: i32,
+    pti_messages_received: i32,
+    pti_syscalls_mach: i32,
+    pti_syscalls_unix: i32,
+    pti_csw: i32,
+    pti_threadnum: i32,
+    pti_numrunning: i32,
+    pti_priority: i32,
+}
+
+#[repr(C)]
+#[allow(dead_code)]
+struct VnodeInfoPath {
+    // `struct vnode_info` (stat, type, fsid); only the path is needed here
+    vip_vi: [u8; 152],
+    vip_path: [c_char; MAXPATHLEN],
+}
+
+#[repr(C)]
+#[allow(dead_code)]
+struct ProcVnodePathInfo {
+    pvi_cdir: VnodeInfoPath,
+    pvi_rdir: VnodeInfoPath,
+}
+
+pub struct MacProcessInspector;
+
+impl MacProcessInspector {
+    /// Fills a `proc_pidinfo` flavor, returning `None` if the pid is gone or
+    /// belongs to another user.
+    fn pid_info<T>(pid: i32, flavor: c_int) -> Option<T> {
+        let mut info = mem::MaybeUninit::<T>::zeroed();
+        let size = mem::size_of::<T>() as c_int;
+        let ret = unsafe { proc_pidinfo(pid, flavor, 0, info.as_mut_ptr() as *mut c_void, size) };
+        if ret == size {
+            Some(unsafe { info.assume_init() })
+        } else {
+            None
+        }
+    }
+
+    /// Converts mach absolute time units (used by `proc_taskinfo`) to nanoseconds.
+    fn mach_to_nanos(ticks: u64) -> u64 {
+        static TIMEBASE: OnceLock<(u32, u32)> = OnceLock::new();
+        let (numer, denom) = *TIMEBASE.get_or_init(|| {
+            let mut info = MachTimebaseInfo::default();
+            unsafe { mach_timebase_info(&mut info) };
+            if info.denom == 0 {
+                (1, 1)
+            } else {
+                (info.numer, info.denom)
+            }
+        });
+        (ticks as u128 * numer as u128 / denom as u128) as u64
+    }
+
+    fn arg_max() -> usize {
+        static ARG_MAX: OnceLock<usize> = OnceLock::new();
+        *ARG_MAX.get_or_init(|| {
+            let mut mib = [libc::CTL_KERN, libc::KERN_ARGMAX];
+            let mut arg_max: c_int = 0;
+            let mut size = mem::size_of::<c_int>();
+            let ret = unsafe {
+                libc::sysctl(mib.as_mut_ptr(), 2, &mut arg_max as *mut c_int as *mut c_void, &mut size, ptr::null_mut(), 0)
+            };
+            if ret == 0 && arg_max > 0 {
+                arg_max as usize
+            } else {
+                256 * 1024
+            }
+        })
+    }
+
+    /// Parses a `KERN_PROCARGS2` buffer: `argc`, the exec path, NUL padding,
+    /// then `argc` NUL-terminated arguments followed by the environment.
+    fn parse_procargs(buffer: &[u8]) -> Option<Vec<String>> {
+        let argc_size = mem::size_of::<c_int>();
+        let argc = c_int::from_ne_bytes(buffer.get(..argc_size)?.try_into().ok()?) as usize;
+        let rest = &buffer[argc_size..];
+
+        // Skip the exec path and the padding after it
+        let exec_end = rest.iter().position(|&b| b == 0)?;
+        let args_start = exec_end + rest[exec_end..].iter().position(|&b| b != 0)?;
+
+        Some(
+            rest[args_start..]
+                .split(|&b| b == 0)
+                .take(argc)
+                .map(|arg| String::from_utf8_lossy(arg).into_owned())
+                .collect(),
+        )
+    }
+}
+
+impl ProcessInspector for MacProcessInspector {
+    fn list_pids(&self) -> Vec<i32> {
+        let mut pids = vec![0i32; 2048];
+        unsafe {
+            let size = proc_listpids(PROC_ALL_PIDS, 0, pids.as_mut_ptr() as *mut c_void, (pids.len() * mem::size_of::<i32>()) as i32);
+            let count = size as usize / mem::size_of::<i32>();
+            pids.truncate(count);
+        }
+        pids.into_iter().filter(|&p| p > 0).collect()
+    }
+
+    fn exe_path(&self, pid: i32) -> Option<String> {
+        let mut buffer = vec![0u8; PROC_PIDPATHINFO_MAXSIZE];
+        unsafe {
+            let ret = proc_pidpath(pid, buffer.as_mut_ptr() as *mut c_char, PROC_PIDPATHINFO_MAXSIZE as u32);
+            if ret > 0 {
+                let cstr = CStr::from_ptr(buffer.as_ptr() as *const c_char);
+                return Some(cstr.to_string_lossy().into_owned());
+            }
+        }
+        None
+    }
+
+    fn argv(&self, pid: i32) -> Option<Vec<String>> {
+        let mut mib = [libc::CTL_KERN, libc::KERN_PROCARGS2, pid];
+        let mut size = Self::arg_max();
+        let mut buffer = vec![0u8; size];
+        let ret = unsafe {
+            libc::sysctl(mib.as_mut_ptr(), 3, buffer.as_mut_ptr() as *mut c_void, &mut size, ptr::null_mut(), 0)
+        };
+        if ret != 0 {
+            return None;
+        }
+        buffer.truncate(size);
+        Self::parse_procargs(&buffer)
+    }
+
+    fn cwd(&self, pid: i32) -> Option<String> {
+        let info = Self::pid_info::<ProcVnodePathInfo>(pid, PROC_PIDVNODEPATHINFO)?;
+        let path = unsafe { CStr::from_ptr(info.pvi_cdir.vip_path.as_ptr()) };
+        let path = path.to_string_lossy();
+        if path.is_empty() {
+            None
+        } else {
+            Some(path.into_owned())
+        }
+    }
+
+    fn cpu_times(&self, pid: i32) -> Option<CpuTimes> {
+        let info = Self::pid_info::<ProcTaskInfo>(pid, PROC_PIDTASKINFO)?;
+        Some(CpuTimes {
+            user: Duration::from_nanos(Self::mach_to_nanos(info.pti_total_user)),
+            system: Duration::from_nanos(Self::mach_to_nanos(info.pti_total_system)),
+        })
+    }
+
+    fn parent_pid(&self, pid: i32) -> Option<i32> {
+        let info = Self::pid_info::<ProcBsdInfo>(pid, PROC_PIDTBSDINFO)?;
+        Some(info.pbi_ppid as i32)
+    }
+
+    fn start_time(&self, pid: i32) -> Option<i64> {
+        let info = Self::pid_info::<ProcBsdInfo>(pid, PROC_PIDTBSDINFO)?;
+        Some(info.pbi_start_tvsec as i64)
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "macos")]
+mod macos;
+mod snapshot;
+mod tree;
+
+pub use snapshot::ProcessSnapshot;
+pub use tree::ProcessTree;
+
+/// Cumulative CPU time a process has consumed since it started.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CpuTimes {
+    pub user: Duration,
+    pub system: Duration,
+}
+
+impl CpuTimes {
+    pub fn total(&self) -> Duration {
+        self.user + self.system
+    }
+}
+
+/// OS-level process queries shared by every `AgentMonitor`.
+///
+/// Monitors only decide *which* processes belong to their agent; how those
+/// processes are enumerated and inspected lives behind this trait so that a
+/// fix (or a new platform) lands once for all agents.
+pub trait ProcessInspector: Send + Sync {
+    fn list_pids(&self) -> Vec<i32>;
+    fn exe_path(&self, pid: i32) -> Option<String>;
+    fn argv(&self, pid: i32) -> Option<Vec<String>>;
+    fn cwd(&self, pid: i32) -> Option<String>;
+    fn cpu_times(&self, pid: i32) -> Option<CpuTimes>;
+    fn parent_pid(&self, pid: i32) -> Option<i32>;
+    /// Process start time as seconds since the Unix epoch.
+    fn start_time(&self, pid: i32) -> Option<i64>;
+
+    fn command_line(&self, pid: i32) -> Option<String> {
+        self.argv(pid).map(|argv| argv.join(" "))
+    }
+}
+
+/// Readings closer together than this repeat the previous one, since CPU
+/// time over a few milliseconds says little and flips busy/idle at random.
+/// Polls triggered back to back (e.g. by a burst of hook events) keep
+/// measuring against the earlier baseline.
+const MIN_SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+
+struct Sample {
+    taken_at: Instant,
+    cpu: Duration,
+    percent: f64,
+}
+
+/// Turns cumulative CPU times into utilisation over the interval between polls.
+///
+/// Lifetime averages such as `ps -o pcpu` decay slowly, so an agent that just
+/// went idle keeps reading as busy. Sampling the delta since the previous poll
+/// makes "busy" mean "busy during the last interval".
+pub struct CpuSampler {
+    inspector: Arc<dyn ProcessInspector>,
+    samples: Mutex<HashMap<i32, Sample>>,
+}
+
+impl CpuSampler {
+    pub fn new(inspector: Arc<dyn ProcessInspector>) -> Self {
+        Self {
+            inspector,
+            samples: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Percent of one core `pid` used since the previous sample, or `None` if
+    /// the process is gone. The first sample of a pid falls back to its
+    /// lifetime average since there is no earlier reading to compare against.
+    pub fn sample(&self, pid: i32) -> Option<f64> {
+        let Some(cpu) = self.inspector.cpu_times(pid).map(|times| times.total()) else {
+            self.forget(pid);
+            return None;
+        };
+        let now = Instant::now();
+
+        let mut samples = self.samples.lock().unwrap();
+        let (cpu_delta, wall_delta) = match samples.get(&pid) {
+            Some(previous) if now - previous.taken_at < MIN_SAMPLE_INTERVAL => return Some(previous.percent),
+            Some(previous) => (cpu.saturating_sub(previous.cpu), now - previous.taken_at),
+            None => {
+                let started = self.inspector.start_time(pid)?;
+                let now_secs = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs() as i64;
+                (cpu, Duration::from_secs((now_secs - started).max(1) as u64))
+            }
+        };
+
+        let percent = if wall_delta.is_zero() {
+            0.0
+        } else {
+            cpu_delta.as_secs_f64() / wall_delta.as_secs_f64() * 100.0
+        };
+        samples.insert(pid, Sample { taken_at: now, cpu, percent });
+        Some(percent)
+    }
+
+    pub fn forget(&self, pid: i32) {
+        self.samples.lock().unwrap().remove(&pid);
+    }
+
+    /// Drops samples of every pid not in `pids`, so exited processes (and
+    /// recycled pids) don't leak or skew the next reading.
+    pub fn retain(&self, pids: &[i32]) {
+        self.samples.lock().unwrap().retain(|pid, _| pids.contains(pid));
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn default_inspector() -> Arc<dyn ProcessInspector> {
+    Arc::new(macos::MacProcessInspector)
+}
+
+#[cfg(target_os = "linux")]
+pub fn default_inspector() -> Arc<dyn ProcessInspector> {
+    Arc::new(linux::LinuxProcessInspector::new())
+}
@@ -0,0 +1,79 @@
+use super::{CpuTimes, ProcessInspector, ProcessTree};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// The process table as of one poll, shared by every monitor so it is walked
+/// once per tick rather than once per agent.
+///
+/// Pids, executable paths and the tree are read up front since every monitor
+/// needs them; argv is read on first use and kept for the rest of the tick.
+/// Anything else (cwd, CPU, start time) is only asked of matched processes
+/// and goes straight to the inspector.
+pub struct ProcessSnapshot {
+    inspector: Arc<dyn ProcessInspector>,
+    pids: Vec<i32>,
+    exe_paths: HashMap<i32, String>,
+    argv: Mutex<HashMap<i32, Option<Vec<String>>>>,
+    tree: ProcessTree,
+}
+
+impl ProcessSnapshot {
+    pub fn take(inspector: Arc<dyn ProcessInspector>) -> Self {
+        let pids = inspector.list_pids();
+        let exe_paths = pids
+            .iter()
+            .filter_map(|&pid| Some((pid, inspector.exe_path(pid)?)))
+            .collect();
+        let tree = ProcessTree::snapshot(inspector.as_ref(), &pids);
+        Self {
+            inspector,
+            pids,
+            exe_paths,
+            argv: Mutex::new(HashMap::new()),
+            tree,
+        }
+    }
+
+    pub fn pids(&self) -> &[i32] {
+        &self.pids
+    }
+
+    pub fn tree(&self) -> &ProcessTree {
+        &self.tree
+    }
+}
+
+impl ProcessInspector for ProcessSnapshot {
+    fn list_pids(&self) -> Vec<i32> {
+        self.pids.clone()
+    }
+
+    fn exe_path(&self, pid: i32) -> Option<String> {
+        self.exe_paths.get(&pid).cloned()
+    }
+
+    fn argv(&self, pid: i32) -> Option<Vec<String>> {
+        self.argv
+            .lock()
+            .unwrap()
+            .entry(pid)
+            .or_insert_with(|| self.inspector.argv(pid))
+            .clone()
+    }
+
+    fn cwd(&self, pid: i32) -> Option<String> {
+        self.inspector.cwd(pid)
+    }
+
+    fn cpu_times(&self, pid: i32) -> Option<CpuTimes> {
+        self.inspector.cpu_times(pid)
+    }
+
+    fn parent_pid(&self, pid: i32) -> Option<i32> {
+        self.tree.parent(pid)
+    }
+
+    fn start_time(&self, pid: i32) -> Option<i64> {
+        self.inspector.start_time(pid)
+    }
+}
@@ -0,0 +1,63 @@
+use super::ProcessInspector;
+use std::collections::{HashMap, HashSet};
+
+/// Parent/child relationships of every process, captured once per poll.
+pub struct ProcessTree {
+    parents: HashMap<i32, i32>,
+    children: HashMap<i32, Vec<i32>>,
+}
+
+impl ProcessTree {
+    pub fn snapshot(inspector: &dyn ProcessInspector, pids: &[i32]) -> Self {
+        let mut parents = HashMap::new();
+        let mut children: HashMap<i32, Vec<i32>> = HashMap::new();
+        for &pid in pids {
+            if let Some(parent) = inspector.parent_pid(pid) {
+                if parent > 0 && parent != pid {
+                    parents.insert(pid, parent);
+                    children.entry(parent).or_default().push(pid);
+                }
+            }
+        }
+        Self { parents, children }
+    }
+
+    pub fn parent(&self, pid: i32) -> Option<i32> {
+        self.parents.get(&pid).copied()
+    }
+
+    pub fn children(&self, pid: i32) -> &[i32] {
+        self.children.get(&pid).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every process below `pid`, breadth first.
+    pub fn descendants(&self, pid: i32) -> Vec<i32> {
+        let mut seen = HashSet::from([pid]);
+        let mut found = vec![];
+        let mut queue = vec![pid];
+        while let Some(current) = queue.pop() {
+            for &child in self.children(current) {
+                if seen.insert(child) {
+                    found.push(child);
+                    queue.push(child);
+                }
+            }
+        }
+        found
+    }
+
+    pub fn has_ancestor_in(&self, pid: i32, pids: &[i32]) -> bool {
+        let mut seen = HashSet::from([pid]);
+        let mut current = pid;
+        while let Some(&parent) = self.parents.get(&current) {
+            if pids.contains(&parent) {
+                return true;
+            }
+            if !seen.insert(parent) {
+                return false;
+            }
+            current = parent;
+        }
+        false
+    }
+}
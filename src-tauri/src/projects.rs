@@ -0,0 +1,51 @@
+use std::sync::RwLock;
+
+use crate::agents::{get_current_dir, is_same_project};
+
+/// Project directories whose agents are monitored: the directory checka was
+/// launched from, plus any handed over by later launches or added through the
+/// control socket.
+pub struct Projects {
+    dirs: RwLock<Vec<String>>,
+}
+
+impl Projects {
+    pub fn new() -> Self {
+        Self {
+            dirs: RwLock::new(get_current_dir().into_iter().collect()),
+        }
+    }
+
+    pub fn list(&self) -> Vec<String> {
+        self.dirs.read().unwrap().clone()
+    }
+
+    /// Returns false when `dir` was already monitored.
+    pub fn add(&self, dir: &str) -> bool {
+        let mut dirs = self.dirs.write().unwrap();
+        if dirs.iter().any(|existing| existing == dir) {
+            return false;
+        }
+        dirs.push(dir.to_string());
+        true
+    }
+
+    /// Returns false when `dir` wasn't monitored.
+    pub fn remove(&self, dir: &str) -> bool {
+        let mut dirs = self.dirs.write().unwrap();
+        let before = dirs.len();
+        dirs.retain(|existing| existing != dir);
+        dirs.len() != before
+    }
+
+    /// Whether a process running in `cwd` belongs to a monitored project.
+    pub fn contains(&self, cwd: &str) -> bool {
+        self.dirs.read().unwrap().iter().any(|dir| is_same_project(cwd, dir))
+    }
+}
+
+impl Default for Projects {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,77 @@
+//! Runs the `[commands]` from `config.toml` when an agent changes status,
+//! e.g. to play a sound or run `git diff --stat` once it finishes.
+
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+
+use crate::agents::AgentStatus;
+use crate::config::{CommandsConfig, SharedConfig};
+use crate::transitions::Transition;
+
+/// Runs commands on a background thread. Each command is started through
+/// `sh -c` in the agent's project directory and never waited on by the
+/// transition loop.
+pub fn spawn(transitions: Receiver<Transition>, config: Arc<SharedConfig>) {
+    std::thread::spawn(move || {
+        for transition in transitions {
+            let commands = config.get().commands;
+            if let Some(command) = command_for(&commands, &transition) {
+                run(command, &transition);
+            }
+        }
+    });
+}
+
+fn command_for<'a>(commands: &'a CommandsConfig, transition: &Transition) -> Option<&'a str> {
+    match (&transition.previous, &transition.status) {
+        (AgentStatus::Processing, AgentStatus::Waiting) => commands.on_finish.as_deref(),
+        (_, AgentStatus::NeedsInput { .. }) => commands.on_needs_input.as_deref(),
+        (previous, AgentStatus::Processing) if *previous != AgentStatus::Processing => {
+            commands.on_start.as_deref()
+        }
+        _ => None,
+    }
+}
+
+fn run(command: &str, transition: &Transition) {
+    let mut process = Command::new("sh");
+    process
+        .arg("-c")
+        .arg(command)
+        .env("CHECKA_AGENT", &transition.agent)
+        .env("CHECKA_PROJECT", transition.project())
+        .env("CHECKA_CWD", &transition.cwd)
+        .env("CHECKA_PID", transition.pid.to_string())
+        .env("CHECKA_PREV", status_name(&transition.previous))
+        .env("CHECKA_STATUS", status_name(&transition.status))
+        .env("CHECKA_DURATION_SECS", format!("{:.0}", transition.previous_duration_secs))
+        .env("CHECKA_TIMESTAMP", &transition.timestamp)
+        .stdin(Stdio::null());
+    if let AgentStatus::NeedsInput { reason } | AgentStatus::Error(reason) = &transition.status {
+        process.env("CHECKA_REASON", reason);
+    }
+    if Path::new(&transition.cwd).is_dir() {
+        process.current_dir(&transition.cwd);
+    }
+
+    match process.spawn() {
+        // Reap the child without blocking the next transition
+        Ok(mut child) => {
+            std::thread::spawn(move || child.wait());
+        }
+        Err(e) => eprintln!("checka: failed to run `{}`: {}", command, e),
+    }
+}
+
+/// Stable names for scripts to compare against, e.g. `[ "$CHECKA_STATUS" = NeedsInput ]`.
+fn status_name(status: &AgentStatus) -> &'static str {
+    match status {
+        AgentStatus::Off => "Off",
+        AgentStatus::Processing => "Processing",
+        AgentStatus::Waiting => "Waiting",
+        AgentStatus::NeedsInput { .. } => "NeedsInput",
+        AgentStatus::Error(_) => "Error",
+    }
+}
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::agent_manager::AgentSummary;
+use crate::agents::AgentStatus;
+
+/// One agent instance changing status between two polls.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transition {
+    /// RFC 3339 local time the change was observed
+    pub timestamp: String,
+    pub agent: String,
+    pub pid: i32,
+    pub cwd: String,
+    pub previous: AgentStatus,
+    pub status: AgentStatus,
+    /// How long the instance spent in `previous`
+    pub previous_duration_secs: f64,
+}
+
+impl Transition {
+    /// Last component of `cwd`, e.g. "my-web-app".
+    pub fn project(&self) -> &str {
+        std::path::Path::new(&self.cwd)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(&self.cwd)
+    }
+}
+
+struct TrackedInstance {
+    status: AgentStatus,
+    cwd: String,
+    since: Instant,
+    /// Seen in two polls. The first CPU reading of a process is its lifetime
+    /// average, so its status isn't trusted until the next one.
+    confirmed: bool,
+    /// First seen after the baseline, so it is reported as coming from `Off`
+    /// once confirmed.
+    appeared: bool,
+}
+
+/// Diffs successive `AgentSummary`s into per-instance transitions.
+///
+/// Instances that appear are reported as coming from `Off` the second time
+/// they are seen, and instances whose process exits as going to `Off`.
+#[derive(Default)]
+pub struct TransitionTracker {
+    instances: HashMap<(String, i32), TrackedInstance>,
+    initialized: bool,
+}
+
+impl TransitionTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records `summary` and returns what changed since the previous call.
+    /// The first call only establishes the baseline and returns nothing.
+    pub fn update(&mut self, summary: &AgentSummary) -> Vec<Transition> {
+        let now = Instant::now();
+        let timestamp = chrono::Local::now().to_rfc3339();
+        let report = self.initialized;
+        self.initialized = true;
+
+        let mut transitions = vec![];
+        let mut seen = vec![];
+
+        for agent in &summary.agents {
+            for instance in &agent.instances {
+                let key = (agent.name.clone(), instance.pid);
+                seen.push(key.clone());
+
+                let (previous, since) = match self.instances.get_mut(&key) {
+                    Some(tracked) if !tracked.confirmed => {
+                        tracked.confirmed = true;
+                        if tracked.status != instance.status {
+                            tracked.status = instance.status.clone();
+                            tracked.since = now;
+                        }
+                        if !(report && tracked.appeared) {
+                            continue;
+                        }
+                        (AgentStatus::Off, now)
+                    }
+                    Some(tracked) if tracked.status == instance.status => continue,
+                    Some(tracked) => (tracked.status.clone(), tracked.since),
+                    None => {
+                        self.instances.insert(
+                            key,
+                            TrackedInstance {
+                                status: instance.status.clone(),
+                                cwd: instance.cwd.clone(),
+                                since: now,
+                                confirmed: false,
+                                appeared: report,
+                            },
+                        );
+                        continue;
+                    }
+                };
+                if report {
+                    transitions.push(Transition {
+                        timestamp: timestamp.clone(),
+                        agent: agent.name.clone(),
+                        pid: instance.pid,
+                        cwd: instance.cwd.clone(),
+                        previous,
+                        status: instance.status.clone(),
+                        previous_duration_secs: (now - since).as_secs_f64(),
+                    });
+                }
+                self.instances.insert(
+                    key,
+                    TrackedInstance {
+                        status: instance.status.clone(),
+                        cwd: instance.cwd.clone(),
+                        since: now,
+                        confirmed: true,
+                        appeared: false,
+                    },
+                );
+            }
+        }
+
+        let gone: Vec<(String, i32)> = self
+            .instances
+            .keys()
+            .filter(|key| !seen.contains(key))
+            .cloned()
+            .collect();
+        for key in gone {
+            let Some(tracked) = self.instances.remove(&key) else { continue };
+            if tracked.confirmed {
+                transitions.push(Transition {
+                    timestamp: timestamp.clone(),
+                    agent: key.0,
+                    pid: key.1,
+                    cwd: tracked.cwd,
+                    previous: tracked.status,
+                    status: AgentStatus::Off,
+                    previous_duration_secs: (now - tracked.since).as_secs_f64(),
+                });
+            }
+        }
+
+        transitions
+    }
+}
+
+/// Fans transitions out to every subsystem that reacts to them.
+#[derive(Default)]
+pub struct TransitionBus {
+    subscribers: Mutex<Vec<Sender<Transition>>>,
+}
+
+impl TransitionBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn subscribe(&self) -> Receiver<Transition> {
+        let (tx, rx) = mpsc::channel();
+        self.subscribers.lock().unwrap().push(tx);
+        rx
+    }
+
+    pub fn publish(&self, transitions: &[Transition]) {
+        // Subscribers that hung up are dropped
+        self.subscribers.lock().unwrap().retain(|subscriber| {
+            transitions
+                .iter()
+                .all(|transition| subscriber.send(transition.clone()).is_ok())
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::{AgentInfo, AgentInstance};
+
+    fn summary(instances: &[(i32, AgentStatus)]) -> AgentSummary {
+        let instances = instances
+            .iter()
+            .map(|(pid, status)| AgentInstance {
+                pid: *pid,
+                status: status.clone(),
+                cwd: "/Users/john/my-web-app".to_string(),
+                started_at: None,
+                cpu_percent: 0.0,
+                activity: None,
+                child_started_at: None,
+            })
+            .collect();
+        let info = AgentInfo {
+            name: "Claude Code".to_string(),
+            status: AgentStatus::Waiting,
+            available: true,
+            last_updated: None,
+            instances,
+            activity: None,
+        };
+        AgentSummary::new(vec![info], 1, "/Users/john/my-web-app".to_string(), vec![])
+    }
+
+    fn changes(transitions: Vec<Transition>) -> Vec<(i32, AgentStatus, AgentStatus)> {
+        transitions
+            .into_iter()
+            .map(|transition| (transition.pid, transition.previous, transition.status))
+            .collect()
+    }
+
+    #[test]
+    fn first_reading_of_an_instance_is_not_reported() {
+        let mut tracker = TransitionTracker::new();
+        // Lifetime CPU averages on the first poll
+        assert!(tracker.update(&summary(&[(1, AgentStatus::Processing)])).is_empty());
+        assert!(tracker.update(&summary(&[(1, AgentStatus::Waiting)])).is_empty());
+        assert_eq!(
+            changes(tracker.update(&summary(&[(1, AgentStatus::Processing)]))),
+            [(1, AgentStatus::Waiting, AgentStatus::Processing)]
+        );
+
+        // A new instance is reported from its second reading on
+        assert!(tracker.update(&summary(&[(1, AgentStatus::Processing), (2, AgentStatus::Processing)])).is_empty());
+        assert_eq!(
+            changes(tracker.update(&summary(&[(1, AgentStatus::Processing), (2, AgentStatus::Waiting)]))),
+            [(2, AgentStatus::Off, AgentStatus::Waiting)]
+        );
+
+        // One that exits before then is never reported at all
+        assert!(tracker.update(&summary(&[(1, AgentStatus::Processing), (2, AgentStatus::Waiting), (3, AgentStatus::Processing)])).is_empty());
+        assert_eq!(
+            changes(tracker.update(&summary(&[(1, AgentStatus::Processing)]))),
+            [(2, AgentStatus::Waiting, AgentStatus::Off)]
+        );
+    }
+}
@@ -3,14 +3,13 @@ use tauri::{
     tray::{MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent},
     AppHandle, Manager,
 };
-use tauri_nspanel::ManagerExt;
 use std::sync::{Arc, Mutex};
 use image::{ImageBuffer, Rgba, RgbaImage, ImageEncoder};
 
-use crate::{agent_manager::AgentSummary, fns::position_menubar_panel};
+use crate::agent_manager::AgentSummary;
 
 pub fn create(app_handle: &AppHandle) -> tauri::Result<TrayIcon> {
-    let initial_icon = get_tray_icon_for_count(0, 0)?;
+    let initial_icon = get_tray_icon_for_count(0, 0, 0)?;
 
     let tray = TrayIconBuilder::with_id("tray")
         .icon(initial_icon)
@@ -20,16 +19,7 @@ pub fn create(app_handle: &AppHandle) -> tauri::Result<TrayIcon> {
 
             if let TrayIconEvent::Click { button_state, .. } = event {
                 if button_state == MouseButtonState::Up {
-                    let panel = app_handle.get_webview_panel("main").unwrap();
-
-                    if panel.is_visible() {
-                        panel.order_out(None);
-                        return;
-                    }
-
-                    position_menubar_panel(app_handle, 0.0);
-
-                    panel.show();
+                    toggle_panel(app_handle);
                 }
             }
         })
@@ -44,8 +34,43 @@ pub fn create(app_handle: &AppHandle) -> tauri::Result<TrayIcon> {
     Ok(tray)
 }
 
-fn get_tray_icon_for_count(processing_count: usize, waiting_count: usize) -> tauri::Result<Image<'static>> {
-    if processing_count == 0 && waiting_count == 0 {
+#[cfg(target_os = "macos")]
+fn toggle_panel(app_handle: &AppHandle) {
+    use tauri_nspanel::ManagerExt;
+
+    let panel = app_handle.get_webview_panel("main").unwrap();
+
+    if panel.is_visible() {
+        panel.order_out(None);
+        return;
+    }
+
+    crate::fns::position_menubar_panel(app_handle, 0.0);
+
+    panel.show();
+}
+
+/// Without the macOS panel the main window is shown and hidden as is.
+#[cfg(not(target_os = "macos"))]
+fn toggle_panel(app_handle: &AppHandle) {
+    let Some(window) = app_handle.get_webview_window("main") else {
+        return;
+    };
+
+    if window.is_visible().unwrap_or(false) {
+        let _ = window.hide();
+        return;
+    }
+
+    let _ = window.show();
+    let _ = window.set_focus();
+}
+
+fn get_tray_icon_for_count(processing_count: usize, waiting_count: usize, needs_input_count: usize) -> tauri::Result<Image<'static>> {
+    if needs_input_count > 0 {
+        // An agent is blocked on the user - use orange eyes
+        generate_robot_head_icon([255, 112, 67]) // #ff7043 orange
+    } else if processing_count == 0 && waiting_count == 0 {
         // All off - use gray eyes
         generate_robot_head_icon([107, 114, 128]) // #6b7280 gray
     } else if processing_count > 0 {
@@ -322,18 +347,28 @@ fn start_tray_updater(app_handle: AppHandle) {
                     if let Some(summary) = summary_lock.as_ref() {
                         let processing_count = summary.processing_count;
                         let waiting_count = summary.waiting_count;
+                        let needs_input_count = summary.needs_input_count;
 
                         if let Some(tray_ref) = app_handle.try_state::<Arc<Mutex<TrayIcon>>>() {
-                            let new_icon = get_tray_icon_for_count(processing_count, waiting_count);
+                            let new_icon = get_tray_icon_for_count(processing_count, waiting_count, needs_input_count);
 
                             if let Ok(icon) = new_icon {
                                 if let Ok(tray) = tray_ref.lock() {
                                     let _ = tray.set_icon(Some(icon));
-                                    let tooltip = match (processing_count, waiting_count) {
-                                        (0, 0) => "AI Agents - All off".to_string(),
-                                        (p, 0) => format!("AI Agents - {} processing", p),
-                                        (0, w) => format!("AI Agents - {} waiting", w),
-                                        (p, w) => format!("AI Agents - {} processing, {} waiting", p, w),
+                                    let counts = [
+                                        (needs_input_count, "needs input"),
+                                        (processing_count, "processing"),
+                                        (waiting_count, "waiting"),
+                                    ];
+                                    let parts: Vec<String> = counts
+                                        .iter()
+                                        .filter(|(count, _)| *count > 0)
+                                        .map(|(count, label)| format!("{} {}", count, label))
+                                        .collect();
+                                    let tooltip = if parts.is_empty() {
+                                        "AI Agents - All off".to_string()
+                                    } else {
+                                        format!("AI Agents - {}", parts.join(", "))
                                     };
                                     let _ = tray.set_tooltip(Some(&tooltip));
                                 }
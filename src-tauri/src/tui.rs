@@ -0,0 +1,258 @@
+//! Full-screen terminal dashboard (`checka tui`) for sessions without the
+//! menubar, e.g. over SSH.
+
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Cell, Paragraph, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::agent_manager::AgentSummary;
+use crate::agents::AgentStatus;
+use crate::cli::{status_label, SummarySource};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const SPARKLINE_WIDTH: usize = 12;
+const SPARKLINE_BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+struct InstanceView {
+    agent: String,
+    pid: i32,
+    status: AgentStatus,
+    since: Instant,
+    cwd: String,
+    activity: Option<String>,
+    cpu_history: VecDeque<f64>,
+}
+
+#[derive(Default)]
+struct App {
+    summary: Option<AgentSummary>,
+    instances: Vec<InstanceView>,
+    /// `None` shows every project, otherwise only instances in that cwd
+    project_filter: Option<String>,
+}
+
+impl App {
+    fn update(&mut self, summary: AgentSummary) {
+        let now = Instant::now();
+        let mut previous: HashMap<(String, i32), InstanceView> = self
+            .instances
+            .drain(..)
+            .map(|view| ((view.agent.clone(), view.pid), view))
+            .collect();
+
+        for agent in &summary.agents {
+            for instance in &agent.instances {
+                let mut view = previous
+                    .remove(&(agent.name.clone(), instance.pid))
+                    .unwrap_or_else(|| InstanceView {
+                        agent: agent.name.clone(),
+                        pid: instance.pid,
+                        status: instance.status.clone(),
+                        since: now,
+                        cwd: instance.cwd.clone(),
+                        activity: None,
+                        cpu_history: VecDeque::with_capacity(SPARKLINE_WIDTH),
+                    });
+
+                if view.status != instance.status {
+                    view.status = instance.status.clone();
+                    view.since = now;
+                }
+                view.cwd = instance.cwd.clone();
+                view.activity = instance.activity.clone();
+                if view.cpu_history.len() == SPARKLINE_WIDTH {
+                    view.cpu_history.pop_front();
+                }
+                view.cpu_history.push_back(instance.cpu_percent);
+                self.instances.push(view);
+            }
+        }
+
+        self.summary = Some(summary);
+    }
+
+    fn projects(&self) -> Vec<String> {
+        let mut projects: Vec<String> = self.instances.iter().map(|view| view.cwd.clone()).collect();
+        projects.sort();
+        projects.dedup();
+        projects
+    }
+
+    /// Steps the filter through "all" and each project with a running agent.
+    fn cycle_project_filter(&mut self) {
+        let projects = self.projects();
+        self.project_filter = match &self.project_filter {
+            None => projects.first().cloned(),
+            Some(current) => projects
+                .iter()
+                .position(|project| project == current)
+                .and_then(|i| projects.get(i + 1).cloned()),
+        };
+    }
+
+    fn visible(&self) -> impl Iterator<Item = &InstanceView> {
+        self.instances.iter().filter(|view| {
+            self.project_filter
+                .as_ref()
+                .is_none_or(|project| &view.cwd == project)
+        })
+    }
+}
+
+/// Runs the dashboard until the user quits.
+pub fn run() -> io::Result<()> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let source = SummarySource::connect();
+        while let Some(summary) = source.get_summary() {
+            if tx.send(summary).is_err() {
+                return;
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    });
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, rx);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, rx: mpsc::Receiver<AgentSummary>) -> io::Result<()> {
+    let mut app = App::default();
+    loop {
+        while let Ok(summary) = rx.try_recv() {
+            app.update(summary);
+        }
+        terminal.draw(|frame| draw(frame, &app))?;
+
+        if !event::poll(Duration::from_millis(200))? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Char('p') => app.cycle_project_filter(),
+                KeyCode::Char('a') => app.project_filter = None,
+                _ => {}
+            }
+        }
+    }
+}
+
+fn status_color(status: &AgentStatus) -> Color {
+    match status {
+        AgentStatus::Off => Color::Gray,
+        AgentStatus::Processing => Color::Green,
+        AgentStatus::Waiting => Color::Yellow,
+        AgentStatus::NeedsInput { .. } => Color::LightRed,
+        AgentStatus::Error(_) => Color::Red,
+    }
+}
+
+fn sparkline(history: &VecDeque<f64>) -> String {
+    history
+        .iter()
+        .map(|&cpu| {
+            // Full scale at one busy core
+            let level = (cpu.clamp(0.0, 100.0) / 100.0 * (SPARKLINE_BARS.len() - 1) as f64).round();
+            SPARKLINE_BARS[level as usize]
+        })
+        .collect()
+}
+
+fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+fn draw(frame: &mut Frame, app: &App) {
+    let [header, body, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(3),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let (directory, updated) = app.summary.as_ref().map_or(("Loading agents...", ""), |summary| {
+        (summary.current_directory.as_str(), summary.last_updated.as_str())
+    });
+    let filter = app.project_filter.as_deref().unwrap_or("all projects");
+    frame.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("Checka ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(format!("📁 {}  ·  showing {}  ·  updated {}", directory, filter, updated)),
+        ])),
+        header,
+    );
+
+    let now = Instant::now();
+    let rows: Vec<Row> = app
+        .visible()
+        .map(|view| {
+            let detail = match &view.status {
+                AgentStatus::NeedsInput { reason } => Some(reason.clone()),
+                _ => view.activity.clone(),
+            };
+            Row::new(vec![
+                Cell::from(view.agent.clone()),
+                Cell::from(status_label(&view.status)).style(Style::default().fg(status_color(&view.status))),
+                Cell::from(format_elapsed(now - view.since)),
+                Cell::from(sparkline(&view.cpu_history)).style(Style::default().fg(Color::Cyan)),
+                Cell::from(format!("{:.1}%", view.cpu_history.back().copied().unwrap_or(0.0))),
+                Cell::from(view.pid.to_string()),
+                Cell::from(view.cwd.clone()),
+                Cell::from(detail.unwrap_or_default()),
+            ])
+        })
+        .collect();
+
+    let empty = rows.is_empty();
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(12),
+            Constraint::Length(12),
+            Constraint::Length(8),
+            Constraint::Length(SPARKLINE_WIDTH as u16),
+            Constraint::Length(7),
+            Constraint::Length(7),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+        ],
+    )
+    .header(
+        Row::new(["AGENT", "STATUS", "IN STATE", "CPU", "", "PID", "CWD", "ACTIVITY"])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::bordered().title(" Agents "));
+    frame.render_widget(table, body);
+
+    if empty && app.summary.is_some() {
+        let [_, message, _] = Layout::vertical([Constraint::Length(2), Constraint::Length(1), Constraint::Min(0)]).areas(body);
+        frame.render_widget(
+            Paragraph::new("  No agents running in this project").style(Style::default().fg(Color::DarkGray)),
+            message,
+        );
+    }
+
+    frame.render_widget(
+        Paragraph::new("q quit  ·  p next project  ·  a all projects").style(Style::default().fg(Color::DarkGray)),
+        footer,
+    );
+}
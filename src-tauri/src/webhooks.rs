@@ -0,0 +1,265 @@
+//! POSTs every status transition as JSON to the `[[webhooks]]` configured in
+//! `config.toml`, e.g. to forward "Claude finished" to team chat.
+
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+
+use crate::config::{SharedConfig, WebhookConfig};
+use crate::transitions::Transition;
+
+pub const SIGNATURE_HEADER: &str = "X-Checka-Signature";
+
+/// First retry delay, doubled after each failed attempt.
+const RETRY_BACKOFF: Duration = Duration::from_secs(1);
+
+#[derive(Serialize)]
+struct Payload<'a> {
+    event: &'static str,
+    host: &'a str,
+    project: &'a str,
+    #[serde(flatten)]
+    transition: &'a Transition,
+}
+
+/// One delivery thread per configured webhook, fed through a queue so a
+/// webhook receives transitions in order even while retrying.
+struct Worker {
+    webhook: WebhookConfig,
+    queue: Sender<String>,
+}
+
+impl Worker {
+    fn start(agent: ureq::Agent, webhook: WebhookConfig) -> Self {
+        let (queue, bodies) = mpsc::channel::<String>();
+        let config = webhook.clone();
+        std::thread::spawn(move || {
+            // Ends once the worker is dropped and its queue drained
+            for body in bodies {
+                if let Err(e) = deliver(&agent, &config, &body) {
+                    eprintln!("checka: webhook {} failed: {}", config.url, e);
+                }
+            }
+        });
+        Self { webhook, queue }
+    }
+}
+
+/// Delivers each transition to the currently configured webhooks. Every
+/// webhook has its own worker, so a slow or retrying endpoint never holds up
+/// the others. Workers follow `reload_config`: unchanged webhooks keep theirs,
+/// removed ones finish their queue and stop.
+pub fn spawn(transitions: Receiver<Transition>, config: Arc<SharedConfig>) {
+    std::thread::spawn(move || {
+        let agent = ureq::agent();
+        let host = host_name();
+        let mut workers: Vec<Worker> = Vec::new();
+        for transition in transitions {
+            let mut previous = std::mem::take(&mut workers);
+            workers = config
+                .get()
+                .webhooks
+                .into_iter()
+                .map(|webhook| match previous.iter().position(|worker| worker.webhook == webhook) {
+                    Some(index) => previous.swap_remove(index),
+                    None => Worker::start(agent.clone(), webhook),
+                })
+                .collect();
+            if workers.is_empty() {
+                continue;
+            }
+
+            let Ok(body) = body(&host, &transition) else {
+                continue;
+            };
+            for worker in &workers {
+                let _ = worker.queue.send(body.clone());
+            }
+        }
+    });
+}
+
+fn body(host: &str, transition: &Transition) -> serde_json::Result<String> {
+    serde_json::to_string(&Payload {
+        event: "status_changed",
+        host,
+        project: transition.project(),
+        transition,
+    })
+}
+
+fn deliver(agent: &ureq::Agent, webhook: &WebhookConfig, body: &str) -> Result<(), String> {
+    let mut backoff = RETRY_BACKOFF;
+    let mut attempt = 0;
+    loop {
+        let mut request = agent
+            .post(&webhook.url)
+            .timeout(Duration::from_secs(webhook.timeout_secs))
+            .set("Content-Type", "application/json")
+            .set("User-Agent", concat!("checka/", env!("CARGO_PKG_VERSION")));
+        if let Some(secret) = &webhook.secret {
+            request = request.set(SIGNATURE_HEADER, &sign(secret, body));
+        }
+
+        let error = match request.send_string(body) {
+            Ok(_) => return Ok(()),
+            // Client errors other than rate limiting won't succeed on retry
+            Err(ureq::Error::Status(code, _)) if code < 500 && code != 429 => {
+                return Err(format!("HTTP {}", code));
+            }
+            Err(ureq::Error::Status(code, _)) => format!("HTTP {}", code),
+            Err(e) => e.to_string(),
+        };
+
+        if attempt >= webhook.retries {
+            return Err(error);
+        }
+        attempt += 1;
+        std::thread::sleep(backoff);
+        backoff *= 2;
+    }
+}
+
+/// `sha256=<hex HMAC of the body>`, the format GitHub uses, so existing
+/// verifiers can be reused.
+pub fn sign(secret: &str, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+fn host_name() -> String {
+    let mut buffer = [0u8; 256];
+    let result = unsafe { libc::gethostname(buffer.as_mut_ptr() as *mut libc::c_char, buffer.len()) };
+    if result != 0 {
+        return "unknown".to_string();
+    }
+    let len = buffer.iter().position(|&b| b == 0).unwrap_or(buffer.len());
+    String::from_utf8_lossy(&buffer[..len]).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::AgentStatus;
+    use std::sync::mpsc::Receiver;
+    use tiny_http::{Response, Server};
+
+    struct Received {
+        signature: Option<String>,
+        body: String,
+    }
+
+    /// Answers each POST with the next of `statuses` and reports what it got.
+    fn stand_in(statuses: Vec<u16>) -> (String, Receiver<Received>) {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", server.server_addr().to_ip().unwrap());
+        let (sender, received) = mpsc::channel();
+        std::thread::spawn(move || {
+            for status in statuses {
+                let Ok(mut request) = server.recv() else {
+                    return;
+                };
+                let mut body = String::new();
+                request.as_reader().read_to_string(&mut body).unwrap();
+                let signature = request
+                    .headers()
+                    .iter()
+                    .find(|header| header.field.equiv(SIGNATURE_HEADER))
+                    .map(|header| header.value.to_string());
+                let _ = sender.send(Received { signature, body });
+                let _ = request.respond(Response::empty(status));
+            }
+        });
+        (url, received)
+    }
+
+    fn webhook(url: String, secret: Option<&str>) -> WebhookConfig {
+        WebhookConfig {
+            url,
+            secret: secret.map(str::to_string),
+            timeout_secs: 5,
+            retries: 2,
+        }
+    }
+
+    fn transition() -> Transition {
+        Transition {
+            timestamp: "2025-06-01T14:03:12+09:00".to_string(),
+            agent: "Claude Code".to_string(),
+            pid: 48213,
+            cwd: "/Users/john/my-web-app".to_string(),
+            previous: AgentStatus::Processing,
+            status: AgentStatus::Waiting,
+            previous_duration_secs: 184.2,
+        }
+    }
+
+    #[test]
+    fn signs_with_hmac_sha256() {
+        assert_eq!(
+            sign("key", "The quick brown fox jumps over the lazy dog"),
+            "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+
+    #[test]
+    fn payload_flattens_the_transition() {
+        let payload: serde_json::Value = serde_json::from_str(&body("my-macbook", &transition()).unwrap()).unwrap();
+        assert_eq!(
+            payload,
+            serde_json::json!({
+                "event": "status_changed",
+                "host": "my-macbook",
+                "project": "my-web-app",
+                "timestamp": "2025-06-01T14:03:12+09:00",
+                "agent": "Claude Code",
+                "pid": 48213,
+                "cwd": "/Users/john/my-web-app",
+                "previous": "Processing",
+                "status": "Waiting",
+                "previous_duration_secs": 184.2,
+            })
+        );
+    }
+
+    #[test]
+    fn sends_the_signed_body() {
+        let (url, received) = stand_in(vec![200]);
+        deliver(&ureq::agent(), &webhook(url, Some("secret")), "{}").unwrap();
+
+        let request = received.recv().unwrap();
+        assert_eq!(request.body, "{}");
+        assert_eq!(request.signature, Some(sign("secret", "{}")));
+    }
+
+    #[test]
+    fn retries_server_errors_and_rate_limits() {
+        let (url, received) = stand_in(vec![503, 429, 200]);
+        deliver(&ureq::agent(), &webhook(url, None), "{}").unwrap();
+
+        assert_eq!(received.try_iter().count(), 3);
+    }
+
+    #[test]
+    fn gives_up_after_the_configured_retries() {
+        let (url, received) = stand_in(vec![500, 500, 500, 200]);
+        let result = deliver(&ureq::agent(), &webhook(url, None), "{}");
+
+        assert_eq!(result, Err("HTTP 500".to_string()));
+        assert_eq!(received.try_iter().count(), 3);
+    }
+
+    #[test]
+    fn does_not_retry_other_client_errors() {
+        let (url, received) = stand_in(vec![404, 200]);
+        let result = deliver(&ureq::agent(), &webhook(url, None), "{}");
+
+        assert_eq!(result, Err("HTTP 404".to_string()));
+        assert_eq!(received.try_iter().count(), 1);
+    }
+}
@@ -1,4 +1,4 @@
-import { AgentInfo, getStatusString, getStatusColor, getStatusIcon } from '../types';
+import { AgentInfo, getStatusString, getStatusColor, getStatusIcon, getStatusDetail, getInstanceBreakdown } from '../types';
 import './AgentStatusItem.css';
 
 interface AgentStatusItemProps {
@@ -9,19 +9,31 @@ export function AgentStatusItem({ agent }: AgentStatusItemProps) {
   const statusString = getStatusString(agent.status);
   const statusColor = getStatusColor(agent.status);
   const statusIcon = getStatusIcon(agent.status);
+  const instanceCount = agent.instances.length;
 
   return (
     <div className="agent-status-item">
       <div className="agent-status-left">
         <span className="status-icon">{statusIcon}</span>
         <span className="agent-name">{agent.name}</span>
+        {instanceCount > 1 && (
+          <span className="instance-count" title={getInstanceBreakdown(agent.instances)}>
+            ×{instanceCount}
+          </span>
+        )}
       </div>
       <div className="agent-status-right">
+        {agent.activity && (
+          <span className="agent-activity" title={agent.activity}>
+            {agent.activity}
+          </span>
+        )}
         <span 
           className="status-text"
           style={{ color: statusColor }}
+          title={getStatusDetail(agent.status)}
         >
-          {statusString}
+          {instanceCount > 1 ? getInstanceBreakdown(agent.instances) : statusString}
         </span>
         {!agent.available && (
           <span className="unavailable-indicator" title="Not installed">
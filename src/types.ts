@@ -1,29 +1,58 @@
-export type AgentStatus = 'Off' | 'Processing' | 'Waiting' | { Error: string };
+export type AgentStatus =
+  | 'Off'
+  | 'Processing'
+  | 'Waiting'
+  | { NeedsInput: { reason: string } }
+  | { Error: string };
+
+export interface AgentInstance {
+  pid: number;
+  status: AgentStatus;
+  cwd: string;
+  started_at?: number;
+  cpu_percent: number;
+  activity?: string;
+}
 
 export interface AgentInfo {
   name: string;
   status: AgentStatus;
   available: boolean;
   last_updated?: string;
+  instances: AgentInstance[];
+  activity?: string;
 }
 
 export interface AgentSummary {
   total_agents: number;
   processing_count: number;
   waiting_count: number;
+  needs_input_count: number;
   active_count: number;
   agents: AgentInfo[];
   last_updated: string;
   current_directory: string;
+  projects: string[];
 }
 
 export function getStatusString(status: AgentStatus): string {
   if (typeof status === 'string') {
     return status;
   }
+  if ('NeedsInput' in status) {
+    return 'Needs Input';
+  }
   return `Error: ${status.Error}`;
 }
 
+// Extra context shown on hover, e.g. which tool is awaiting permission
+export function getStatusDetail(status: AgentStatus): string | undefined {
+  if (typeof status === 'object' && 'NeedsInput' in status) {
+    return status.NeedsInput.reason;
+  }
+  return undefined;
+}
+
 export function getStatusColor(status: AgentStatus): string {
   if (typeof status === 'string') {
     switch (status) {
@@ -33,6 +62,9 @@ export function getStatusColor(status: AgentStatus): string {
       default: return '#6b7280';
     }
   }
+  if ('NeedsInput' in status) {
+    return '#ff7043'; // orange
+  }
   return '#ef4444'; // red for error
 }
 
@@ -45,5 +77,18 @@ export function getStatusIcon(status: AgentStatus): string {
       default: return '⚪';
     }
   }
+  if ('NeedsInput' in status) {
+    return '🟠';
+  }
   return '🔴'; // red for error
-}
\ No newline at end of file
+}
+
+// e.g. "1 processing, 1 waiting"
+export function getInstanceBreakdown(instances: AgentInstance[]): string {
+  const counts = new Map<string, number>();
+  for (const instance of instances) {
+    const status = getStatusString(instance.status).toLowerCase();
+    counts.set(status, (counts.get(status) ?? 0) + 1);
+  }
+  return Array.from(counts, ([status, count]) => `${count} ${status}`).join(', ');
+}